@@ -0,0 +1,471 @@
+//! Ids given to the headings of the chapters, see `output.api.anchors`.
+//!
+//! The ids of the whole book are computed up front in `SUMMARY.md` order, so
+//! that the headings, the table of contents and the links between chapters
+//! agree on them whatever the order the chapters are rendered in.
+//!
+//! The ids of every page are written to `anchors.json`, the next build warns
+//! about, or fails on, the anchors it no longer has unless they are redirected
+//! with `output.api.redirects`.
+
+use crate::api::front_matter;
+use crate::api::util::{escape, json_attribute, resolve_path};
+
+use pulldown_cmark::{CowStr, Event, Tag};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mdbook::book::{Book, BookItem};
+use mdbook::errors::Result;
+use mdbook::utils;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorStrategy {
+    /// The normalized heading text, e.g. `errors`
+    #[default]
+    Plain,
+    /// The heading text prefixed with the chapter path, e.g. `users-list-errors`
+    Prefixed,
+    /// The heading text followed by a counter when it is already used by a
+    /// previous heading of the page, e.g. `errors-1`
+    Dedup,
+}
+
+#[derive(Debug, Clone)]
+struct Anchor {
+    /// Id of the heading with the plain strategy, used by links
+    plain: String,
+    id: String,
+}
+
+/// Heading ids of every chapter of a book, by chapter path.
+#[derive(Debug, Clone, Default)]
+pub struct Anchors {
+    chapters: HashMap<PathBuf, Vec<Anchor>>,
+}
+
+impl Anchors {
+    /// Ids of the headings of `book`, each chapter being its own page unless
+    /// `single_page`.
+    pub fn from_book(book: &Book, strategy: AnchorStrategy, single_page: bool) -> Self {
+        let mut used = HashSet::new();
+        let mut chapters = HashMap::new();
+
+        for item in book.iter() {
+            if let BookItem::Chapter(ref ch) = *item {
+                let prefix = chapter_slug(&ch.path);
+                if !single_page {
+                    used.clear();
+                }
+
+                let anchors = heading_texts(&ch.content)
+                    .iter()
+                    .map(|text| {
+                        let plain = utils::normalize_id(text);
+                        let id = match strategy {
+                            AnchorStrategy::Plain => plain.clone(),
+                            AnchorStrategy::Prefixed => format!("{}-{}", prefix, plain),
+                            AnchorStrategy::Dedup => unique_id(&plain, &mut used),
+                        };
+                        Anchor { plain, id }
+                    })
+                    .collect();
+
+                chapters.insert(ch.path.clone(), anchors);
+            }
+        }
+
+        Anchors { chapters }
+    }
+
+    /// Ids of the headings of a chapter, in order
+    pub fn ids(&self, chapter: &Path) -> Vec<&str> {
+        self.chapters
+            .get(chapter)
+            .map(|anchors| anchors.iter().map(|anchor| anchor.id.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Id of the first heading of a chapter, the target of its TOC entry
+    pub fn first(&self, chapter: &Path) -> Option<&str> {
+        let anchors = self.chapters.get(chapter)?;
+        anchors.first().map(|anchor| anchor.id.as_str())
+    }
+
+    /// Id of the heading of a chapter written `fragment` in links
+    pub fn resolve(&self, chapter: &Path, fragment: &str) -> Option<&str> {
+        let anchors = self.chapters.get(chapter)?;
+        anchors
+            .iter()
+            .find(|anchor| anchor.plain == fragment)
+            .map(|anchor| anchor.id.as_str())
+    }
+
+    /// Give the headings of a chapter their id and point the links to the ids
+    /// of the targeted headings.
+    pub fn fix_events<'s, 'a: 's, I>(
+        &'s self,
+        events: I,
+        chapter: &'s Path,
+    ) -> impl Iterator<Item = Event<'a>> + 's
+    where
+        I: Iterator<Item = Event<'a>> + 's,
+    {
+        let mut ids = self.ids(chapter).into_iter();
+
+        events.map(move |event| match event {
+            Event::Start(Tag::Heading(level)) => match ids.next() {
+                Some(id) => Event::Html(format!("<h{} id=\"{}\">", level, id).into()),
+                None => Event::Start(Tag::Heading(level)),
+            },
+            Event::Start(Tag::Link(link_type, dest, title)) => {
+                Event::Start(Tag::Link(link_type, self.fix_link(dest, chapter), title))
+            }
+            event => event,
+        })
+    }
+
+    /// Point the fragment of a link to a chapter at the id of the heading.
+    fn fix_link<'a>(&self, dest: CowStr<'a>, chapter: &Path) -> CowStr<'a> {
+        let (target, fragment) = match dest.find('#') {
+            Some(index) => (&dest[..index], &dest[index + 1..]),
+            None => return dest,
+        };
+
+        let target_chapter = match target {
+            "" => chapter.to_path_buf(),
+            target if target.ends_with(".html") && !target.contains(':') => {
+                resolve_path(chapter.parent().unwrap_or_else(|| Path::new("")), target)
+                    .with_extension("md")
+            }
+            _ => return dest,
+        };
+
+        match self.resolve(&target_chapter, fragment) {
+            Some(id) if id != fragment => format!("{}#{}", target, id).into(),
+            _ => dest,
+        }
+    }
+
+    /// Point the links to the chapters at their heading in the single page
+    /// gathering the whole book.
+    pub fn fix_single_page_links(&self, html: &str, chapter: &Path) -> String {
+        lazy_static! {
+            static ref LINK: Regex = Regex::new(r##"href="([^"#:]+\.html)(#[^"]*)?""##).unwrap();
+        }
+
+        LINK.replace_all(html, |caps: &Captures<'_>| {
+            let target = resolve_path(chapter.parent().unwrap_or_else(|| Path::new("")), &caps[1])
+                .with_extension("md");
+
+            if !self.chapters.contains_key(&target) {
+                return caps[0].to_owned();
+            }
+
+            match caps.get(2) {
+                Some(fragment) => format!("href=\"{}\"", fragment.as_str()),
+                None => match self.first(&target) {
+                    Some(id) => format!("href=\"#{}\"", id),
+                    None => caps[0].to_owned(),
+                },
+            }
+        })
+        .into_owned()
+    }
+}
+
+/// Text of the headings of a chapter the plain ids are made of. It is the
+/// escaped HTML of the heading up to its first tag, as the ids were given to
+/// the rendered headings before, so that links to them keep working. Headings
+/// starting with a tag use their whole text.
+fn heading_texts(content: &str) -> Vec<String> {
+    let mut texts = vec![];
+    // Escaped text before the first tag, whole text and whether a tag was met
+    let mut current: Option<(String, String, bool)> = None;
+
+    for event in utils::new_cmark_parser(front_matter::strip(content)) {
+        match (event, current.as_mut()) {
+            (Event::Start(Tag::Heading(_)), _) => {
+                current = Some((String::new(), String::new(), false));
+            }
+            (Event::End(Tag::Heading(_)), _) => {
+                texts.extend(current.take().map(
+                    |(html, text, _)| {
+                        if html.is_empty() {
+                            text
+                        } else {
+                            html
+                        }
+                    },
+                ));
+            }
+            (Event::Text(text), Some((html, whole, tagged))) => {
+                if !*tagged {
+                    html.push_str(&escape(&text));
+                }
+                whole.push_str(&text);
+            }
+            (Event::SoftBreak, Some((html, whole, tagged))) => {
+                if !*tagged {
+                    html.push('\n');
+                }
+                whole.push('\n');
+            }
+            (Event::Code(text), Some((_, whole, tagged))) => {
+                *tagged = true;
+                whole.push_str(&text);
+            }
+            (_, Some((_, _, tagged))) => *tagged = true,
+            _ => {}
+        }
+    }
+
+    texts
+}
+
+/// The chapter path as an id, e.g. `users-list` for `users/list.md`.
+fn chapter_slug(path: &Path) -> String {
+    let path = path.with_extension("");
+    let parts: Vec<_> = path
+        .components()
+        .map(|component| utils::normalize_id(&component.as_os_str().to_string_lossy()))
+        .collect();
+    parts.join("-")
+}
+
+fn unique_id(id: &str, used: &mut HashSet<String>) -> String {
+    if used.insert(id.to_owned()) {
+        return id.to_owned();
+    }
+
+    (1..)
+        .map(|counter| format!("{}-{}", id, counter))
+        .find(|candidate| used.insert(candidate.clone()))
+        .unwrap_or_default()
+}
+
+/// Name of the anchor manifest written at the root of the output.
+pub const ANCHOR_MANIFEST: &str = "anchors.json";
+
+/// What to do when an anchor of the previous build is gone.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RemovedAnchors {
+    Ignore,
+    #[default]
+    Warn,
+    Fail,
+}
+
+/// Every page of the output with the ids of its headings, written as
+/// `anchors.json` to compare the anchors from one build to the next.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AnchorManifest {
+    pages: BTreeMap<String, Vec<String>>,
+}
+
+impl AnchorManifest {
+    /// Pages of the one page output, or of one page per chapter
+    pub fn new(book: &Book, anchors: &Anchors, single_page: bool) -> Self {
+        let mut pages: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for (index, item) in book.iter().enumerate() {
+            if let BookItem::Chapter(ref ch) = *item {
+                let page = if single_page || index == 0 {
+                    "index.html".to_owned()
+                } else {
+                    page_name(&ch.path)
+                };
+
+                let ids = anchors.ids(&ch.path).into_iter().map(str::to_owned);
+                pages.entry(page).or_default().extend(ids);
+            }
+        }
+
+        AnchorManifest { pages }
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read(path).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    pub fn to_vec(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    /// Whether the output has a page and its anchor if any, e.g.
+    /// `users/list.html#errors`
+    pub fn contains(&self, anchor: &str) -> bool {
+        let (page, id) = split_anchor(anchor);
+        self.pages
+            .get(page)
+            .is_some_and(|ids| id.is_empty() || ids.iter().any(|known| known == id))
+    }
+
+    /// Report the redirects to unknown anchors, and the anchors of the
+    /// `previous` build which are neither in this one nor redirected unless
+    /// `mode` ignores them.
+    pub fn check(
+        &self,
+        previous: Option<&AnchorManifest>,
+        redirects: &BTreeMap<String, String>,
+        mode: RemovedAnchors,
+    ) -> Result<()> {
+        let mut problems = vec![];
+
+        if let (Some(previous), false) = (previous, mode == RemovedAnchors::Ignore) {
+            for (page, ids) in &previous.pages {
+                for id in ids {
+                    let anchor = format!("{}#{}", page, id);
+                    if !self.contains(&anchor) && !redirects.contains_key(&anchor) {
+                        problems.push(format!("The anchor {} was removed", anchor));
+                    }
+                }
+            }
+        }
+
+        for (old, new) in redirects {
+            if !self.contains(new) {
+                problems.push(format!(
+                    "The redirect of {} targets an unknown anchor {}",
+                    old, new
+                ));
+            }
+        }
+
+        for problem in &problems {
+            warn!("{}", problem);
+        }
+
+        match (mode, problems.len()) {
+            (RemovedAnchors::Fail, count) if count > 0 => Err(format!(
+                "{} broken anchor(s), add them to `output.api.redirects`",
+                count
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Write the anchors of `output.api.redirects` as `page.html#id`, an anchor
+/// without page being on `index.html`.
+pub fn normalize_redirects(redirects: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    redirects
+        .iter()
+        .map(|(old, new)| (normalize_anchor(old), normalize_anchor(new)))
+        .collect()
+}
+
+fn normalize_anchor(anchor: &str) -> String {
+    match anchor.find('#') {
+        Some(0) => format!("index.html{}", anchor),
+        Some(_) => anchor.to_owned(),
+        None => format!("index.html#{}", anchor),
+    }
+}
+
+fn split_anchor(anchor: &str) -> (&str, &str) {
+    match anchor.find('#') {
+        Some(index) => (&anchor[..index], &anchor[index + 1..]),
+        None => (anchor, ""),
+    }
+}
+
+/// Redirects of the anchors of a page as a JSON object, read by `api.js`,
+/// from the old id to the link to the new anchor.
+pub fn page_redirects(
+    redirects: &BTreeMap<String, String>,
+    page: &str,
+    path_to_root: &str,
+) -> Result<String> {
+    let mut links = BTreeMap::new();
+
+    for (old, new) in redirects {
+        let (old_page, old_id) = split_anchor(old);
+        if old_page != page {
+            continue;
+        }
+
+        let link = match split_anchor(new) {
+            (new_page, new_id) if new_page == page => format!("#{}", new_id),
+            _ => format!("{}{}", path_to_root, new),
+        };
+        links.insert(old_id, link);
+    }
+
+    json_attribute(&links)
+}
+
+/// Output page of a chapter, e.g. `users/list.html`.
+pub fn page_name(chapter: &Path) -> String {
+    let page = chapter.with_extension("html");
+    let parts: Vec<_> = page
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    parts.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mdbook::book::Chapter;
+
+    fn book(chapters: &[(&str, &str)]) -> Book {
+        let mut book = Book::new();
+        for &(path, content) in chapters {
+            let name = path.trim_end_matches(".md");
+            book.push_item(Chapter::new(name, content.to_owned(), path, vec![]));
+        }
+        book
+    }
+
+    #[test]
+    fn plain_ids_from_the_escaped_heading() {
+        let book = book(&[(
+            "orders.md",
+            "# Orders & Things\n## `Order` fields\n## Use `limit` to page\n## \\<Item\\>\n",
+        )]);
+        let anchors = Anchors::from_book(&book, AnchorStrategy::Plain, false);
+
+        assert_eq!(
+            anchors.ids(Path::new("orders.md")),
+            vec!["orders-amp-things", "order-fields", "use-", "ltitemgt"]
+        );
+    }
+
+    #[test]
+    fn dedup_ids_per_page() {
+        let book = book(&[
+            ("users.md", "# Users\n## Errors\n## Errors\n"),
+            ("orders.md", "# Orders\n## Errors\n"),
+        ]);
+
+        let anchors = Anchors::from_book(&book, AnchorStrategy::Dedup, false);
+        assert_eq!(
+            anchors.ids(Path::new("users.md")),
+            vec!["users", "errors", "errors-1"]
+        );
+        assert_eq!(
+            anchors.ids(Path::new("orders.md")),
+            vec!["orders", "errors"]
+        );
+
+        let anchors = Anchors::from_book(&book, AnchorStrategy::Dedup, true);
+        assert_eq!(
+            anchors.ids(Path::new("orders.md")),
+            vec!["orders", "errors-2"]
+        );
+        assert_eq!(
+            anchors.resolve(Path::new("orders.md"), "errors"),
+            Some("errors-2")
+        );
+    }
+}
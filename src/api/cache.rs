@@ -0,0 +1,154 @@
+//! Build cache used by incremental rebuilds, see `output.api.incremental`.
+//!
+//! The cache records a hash of the content of each chapter and a global hash
+//! covering everything else a page depends on: the configuration, the book
+//! structure, the heading ids and front matter of the chapters, the current
+//! commit with `output.api.git_metadata`, the text of the interface, the
+//! templates and the static files of the theme. When the global hash changes
+//! the whole book is rebuilt, otherwise only the chapters whose content
+//! changed are rendered again.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::api::engine::{ApiConfig, BookState};
+use crate::api::git;
+use crate::api::i18n;
+use crate::api::util::hex;
+use crate::theme::Theme;
+
+use mdbook::book::BookItem;
+use mdbook::errors::Result;
+use mdbook::errors::ResultExt;
+use mdbook::renderer::RenderContext;
+
+/// Bumped when the layout of the cache changes.
+const CACHE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct BuildCache {
+    version: u32,
+    /// Hash of everything but the chapters content
+    global: String,
+    /// Hash of the content of each chapter, indexed by path
+    chapters: BTreeMap<String, String>,
+}
+
+impl BuildCache {
+    /// The cache is stored next to the destination folder, e.g.
+    /// `book/.api-cache.json` for `book/api`.
+    pub fn path(destination: &Path) -> PathBuf {
+        let name = destination
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        destination.with_file_name(format!(".{}-cache.json", name))
+    }
+
+    /// Hash the current state of the book, `renderer` names the kind of
+    /// output, e.g. one page or one page per chapter.
+    pub fn from_context<T: Theme>(
+        ctx: &RenderContext,
+        renderer: &str,
+        theme: &T,
+        book_state: &BookState,
+    ) -> Result<Self> {
+        let mut global = Sha256::new();
+        global.update(env!("CARGO_PKG_VERSION"));
+        global.update(renderer);
+        global.update(serde_json::to_vec(&ctx.config)?);
+        global.update(theme.get_template());
+
+        for (name, content) in theme.get_assets() {
+            global.update(name);
+            global.update(Sha256::digest(content));
+        }
+
+        // The strings may come from the theme folder
+        global.update(serde_json::to_vec(&i18n::load(ctx)?)?);
+
+        // The page served for missing paths is only rendered with the book
+        if let Ok(not_found) = fs::read(ctx.source_dir().join("404.md")) {
+            global.update(Sha256::digest(&not_found));
+        }
+
+        let api_config = ApiConfig::from_context(ctx);
+
+        // Every commit may change the last commit shown on the pages
+        if api_config.git_metadata {
+            global.update(git::head(&ctx.source_dir()).unwrap_or_default());
+        }
+
+        // The locale menu links to the heading ids of the other editions
+        for (locale, anchors) in book_state.editions.iter() {
+            global.update(format!("edition {}\n", locale.id));
+            for item in ctx.book.iter() {
+                if let BookItem::Chapter(ref ch) = *item {
+                    global.update(anchors.ids(&ch.path).join(" "));
+                }
+            }
+        }
+
+        // The heading ids are shared by the table of contents and the links
+        let anchors = &book_state.anchors;
+
+        let mut chapters = BTreeMap::new();
+        for item in ctx.book.iter() {
+            match *item {
+                BookItem::Chapter(ref ch) => {
+                    let path = ch.path.to_string_lossy().into_owned();
+                    let number = ch.number.as_ref().map(|number| number.to_string());
+                    global.update(format!("chapter {} {} {:?}\n", path, ch.name, number));
+                    global.update(anchors.ids(&ch.path).join(" "));
+
+                    // The front matter changes the table of contents and the
+                    // language menu of the other pages
+                    if let Some((keys, _)) = book_state.front_matter.get(&ch.path) {
+                        global.update(serde_json::to_vec(keys)?);
+                    }
+
+                    chapters.insert(path, hex(&Sha256::digest(ch.content.as_bytes())));
+                }
+                BookItem::Separator => global.update("separator\n"),
+            }
+        }
+
+        Ok(BuildCache {
+            version: CACHE_VERSION,
+            global: hex(&global.finalize()),
+            chapters,
+        })
+    }
+
+    /// Load the cache of the previous build, if any.
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read(path).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)
+            .chain_err(|| format!("Unable to write the build cache {}", path.display()))
+    }
+
+    /// Paths of the chapters which changed since the `previous` build, or
+    /// `None` if the whole book must be rebuilt.
+    pub fn changed_chapters(&self, previous: &BuildCache) -> Option<HashSet<String>> {
+        if self.version != previous.version || self.global != previous.global {
+            return None;
+        }
+
+        let changed = self
+            .chapters
+            .iter()
+            .filter(|&(path, hash)| previous.chapters.get(path) != Some(hash))
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        Some(changed)
+    }
+}
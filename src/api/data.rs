@@ -0,0 +1,161 @@
+//! Data model handed to the Handlebars templates.
+//!
+//! Every value a theme can reference from `index.hbs` is a field of
+//! [`TemplateData`]. Run `mdbook-api print-context` in a book directory to
+//! dump the data of a given page.
+
+use crate::api::engine::{CodeTheme, ExternalScript, LangLink, TocFooter};
+use crate::api::git::LastCommit;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Version of the template data layout, bumped on every breaking change.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Data available in the templates when rendering a page.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TemplateData {
+    /// Version of the data layout, see [`SCHEMA_VERSION`]
+    pub schema_version: u32,
+
+    /// Language of the book, from `book.language`
+    pub language: String,
+    /// Direction of the text in the language of the book, `ltr` or `rtl`
+    pub text_direction: String,
+    /// Text of the user interface in the language of the book, by key, see
+    /// `output.api.i18n`
+    pub strings: BTreeMap<String, String>,
+    /// `strings` as a JSON object, read by `api.js`
+    pub strings_json: String,
+    /// Title of the book, from `book.title`
+    pub book_title: String,
+    /// Description of the page, from the `description` front matter key, the
+    /// first paragraph of the chapter or `book.description`
+    pub description: String,
+    /// Path of the favicon, relative to the root
+    pub favicon: String,
+    /// Livereload websocket url, set when served with `mdbook serve`
+    pub livereload: Option<String>,
+    /// Whether assets are inlined in the page, see `output.api.self_contained`
+    pub self_contained: bool,
+    /// URL of the root of the book, from `output.api.site_url` or
+    /// `output.api.base_path`, `/` when none is set
+    pub base_url: String,
+    /// Whether section numbers are displayed, see `output.api.section_numbers`
+    pub section_numbers: bool,
+    /// Heading levels indexed by the search, read by `api.js`. All of them in
+    /// the one page output when `output.api.shift_headings` is set.
+    pub search_headings: String,
+    /// Stylesheets of the code palettes by colour scheme, see
+    /// `output.api.code_theme`
+    pub code_theme: CodeTheme,
+
+    /// Language ids as a JSON array, read by `app.js`
+    pub lang_list: String,
+    /// Languages displayed in the language selector
+    pub languages: Vec<LangLink>,
+    /// Links displayed under the table of contents
+    pub toc_footer: Vec<TocFooter>,
+    /// Scripts loaded from another origin, see `output.api.external_script`
+    pub external_scripts: Vec<ExternalScript>,
+    /// Table of contents, as a tree following `SUMMARY.md`
+    pub chapters: Vec<TocItem>,
+
+    /// Path of the page source, relative to the source directory
+    pub path: String,
+    /// Relative path from the page to the root of the output
+    pub path_to_root: String,
+    /// Title of the page, chapter name followed by the book title, or the book
+    /// title for the whole book page
+    pub title: String,
+    /// Name of the chapter
+    pub chapter_title: String,
+    /// Section number of the chapter, e.g. `1.2.`
+    pub section: Option<String>,
+    /// Rendered HTML content of the page
+    pub content: String,
+    /// Whether the page is the index of the book
+    pub is_index: bool,
+    /// Redirected anchors of the page as a JSON object, from the old id to
+    /// the new link, read by `api.js`
+    pub anchor_redirects: String,
+    /// Public URL of the page, set with `output.api.site_url`
+    pub canonical_url: Option<String>,
+    /// URL of the image of the link previews, see `output.api.social_image`
+    pub social_image: Option<String>,
+    /// Twitter account of the site, see `output.api.twitter_site`
+    pub twitter_site: Option<String>,
+    /// Last commit changing the chapter, see `output.api.git_metadata`
+    pub last_commit: Option<LastCommit>,
+    /// When and by whom the chapter was last changed, in the language of the
+    /// book
+    pub last_updated: Option<String>,
+    /// URL editing the source of the chapter, see
+    /// `output.api.edit_url_template`
+    pub edit_url: Option<String>,
+    /// Previous chapter in `SUMMARY.md`
+    pub previous: Option<NavLink>,
+    /// Next chapter in `SUMMARY.md`
+    pub next: Option<NavLink>,
+    /// Chapter the page is nested in
+    pub parent: Option<NavLink>,
+    /// Chapters the page is nested in, starting from the top level
+    pub breadcrumbs: Vec<NavLink>,
+    /// The page in every edition of the book, see `output.api.locale`
+    pub locales: Vec<LocaleLink>,
+    /// Heading ids of the chapters of the page in every edition as a JSON
+    /// object, read by `api.js`
+    pub locale_anchors: String,
+    /// `TechArticle` description of the page as JSON-LD, see
+    /// `output.api.json_ld`
+    pub json_ld: Option<String>,
+    /// Keys of the chapter front matter, as written
+    pub front_matter: BTreeMap<String, Value>,
+}
+
+/// Link to another chapter of the book, relative to the page.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NavLink {
+    /// Name of the chapter
+    pub name: String,
+    pub link: String,
+}
+
+/// Link to a page in an edition of the book.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LocaleLink {
+    /// Language id of the edition
+    pub id: String,
+    /// Display name of the language
+    pub name: String,
+    pub link: String,
+    /// Whether the page is in this edition
+    pub is_current: bool,
+}
+
+/// An entry of the table of contents.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TocItem {
+    /// Name of the chapter, empty for separators
+    pub name: String,
+    /// Path of the chapter source, relative to the source directory
+    pub path: String,
+    /// Id of the first heading of the chapter, see `output.api.anchors`
+    pub anchor: String,
+    /// Section number of the chapter, e.g. `1.2.`, `None` for the prefix and
+    /// suffix chapters
+    pub section: Option<String>,
+    /// Depth of the entry in the tree, starting at 1
+    pub level: usize,
+    /// Whether the entry is a separator between chapters
+    pub is_separator: bool,
+    /// Whether the chapter is hidden from the table of contents, see the
+    /// `hide_toc` front matter key
+    pub is_hidden: bool,
+    /// Whether the chapter has nested chapters
+    pub has_sub_items: bool,
+    /// Nested chapters
+    pub sub_items: Vec<TocItem>,
+}
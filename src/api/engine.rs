@@ -1,58 +1,301 @@
+use crate::api::anchors::{self, AnchorStrategy, Anchors, RemovedAnchors};
+use crate::api::data::{LocaleLink, NavLink, TemplateData, TocItem, SCHEMA_VERSION};
+use crate::api::front_matter::{self, FrontMatter};
+use crate::api::git::{self, LastCommit};
+use crate::api::i18n;
+use crate::api::locale::Locale;
+use crate::api::optimize::OptimizeConfig;
 use crate::api::parser::parser_from_str;
+use crate::api::sitemap;
+use crate::api::util::{has_scheme, is_absolute, json_attribute};
 use crate::engine::Engine;
 
-use pulldown_cmark::html;
+use handlebars::html_escape;
+use pulldown_cmark::{html, Event, Tag};
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use mdbook::book::BookItem;
+use mdbook::book::{Book, BookItem};
 use mdbook::errors::Result;
 use mdbook::errors::ResultExt;
 use mdbook::renderer::RenderContext;
 use mdbook::utils;
 
 #[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
 pub struct ApiConfig {
     pub theme_dir: Option<String>,
     pub toc_footer: Option<Vec<TocFooter>>,
     pub lang: Vec<Language>,
+    /// Write one page per chapter instead of gathering the book in one page
+    pub multi_page: bool,
+    /// Inline all the assets into the generated pages
+    pub self_contained: bool,
+    /// Add a content hash to the name of the theme assets
+    pub fingerprint: bool,
+    /// Minify and precompress the generated files
+    pub optimize: OptimizeConfig,
+    /// Only render the chapters which changed since the previous build
+    pub incremental: bool,
+    /// Scripts loaded from another origin, with their integrity hash
+    pub external_script: Vec<ExternalScript>,
+    /// Show the section numbers in the table of contents and the headings
+    pub section_numbers: bool,
+    /// Shift the headings of the nested chapters by their depth in the one
+    /// page output
+    pub shift_headings: bool,
+    /// How the heading ids are built: `plain`, `prefixed` or `dedup`, see
+    /// `ApiConfig::anchor_strategy` for the default
+    pub anchors: Option<AnchorStrategy>,
+    /// What to do with the anchors of the previous build which are gone:
+    /// `ignore`, `warn` or `fail`
+    pub removed_anchors: RemovedAnchors,
+    /// Old anchors, e.g. `users/list.html#errors`, and the anchors replacing
+    /// them
+    pub redirects: BTreeMap<String, String>,
+    /// Old paths of moved pages and their new path, with an optional anchor
+    pub redirect: BTreeMap<String, String>,
+    /// Public URL of the book, e.g. `https://example.com/docs/`
+    pub site_url: Option<String>,
+    /// Path the book is served from, e.g. `/docs/api/`, when `site_url` is
+    /// not set
+    pub base_path: Option<String>,
+    /// Image shown in the link previews, a URL or relative to `site_url`
+    pub social_image: Option<String>,
+    /// Twitter account of the site, e.g. `@example`
+    pub twitter_site: Option<String>,
+    /// Describe the pages as a `TechArticle` in JSON-LD
+    pub json_ld: bool,
+    /// Show the last commit of the chapters, from the git repository of the
+    /// book
+    pub git_metadata: bool,
+    /// URL editing the source of a chapter, `{path}` is replaced by the path
+    /// of the chapter, e.g. `https://git.example/edit/main/src/{path}`
+    pub edit_url_template: Option<String>,
+    /// Syntax highlighting palette of each colour scheme
+    pub code_theme: CodeTheme,
+    /// Text of the user interface replacing the built-in one, by key
+    pub i18n: BTreeMap<String, String>,
+    /// Editions of the book in other languages, the first being the default
+    pub locale: Vec<Locale>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+impl ApiConfig {
+    /// Load the `output.api` table, falling back to the default configuration
+    pub fn from_context(ctx: &RenderContext) -> Self {
+        match ctx.config.get_deserialized_opt("output.api") {
+            Ok(Some(config)) => Some(config),
+            _ => None,
+        }
+        .unwrap_or_default()
+    }
+
+    /// Folder overloading the default theme, `theme` by default
+    pub fn theme_dir(&self, root: &Path) -> PathBuf {
+        match self.theme_dir {
+            Some(ref path) => root.join(path),
+            None => root.join("theme"),
+        }
+    }
+
+    /// How the heading ids are built. Chapters gathered in one page often
+    /// share headings, e.g. `Errors`, the ids are then made unique by default.
+    pub fn anchor_strategy(&self, single_page: bool) -> AnchorStrategy {
+        match self.anchors {
+            Some(strategy) => strategy,
+            None if single_page => AnchorStrategy::Dedup,
+            None => AnchorStrategy::Plain,
+        }
+    }
+
+    /// URL of the root of the book for the pages served at any path, from
+    /// `site_url`, `base_path` or `/`
+    pub fn site_root(&self) -> String {
+        let root = match (&self.site_url, &self.base_path) {
+            (Some(url), _) => url.clone(),
+            (None, Some(path)) if path.starts_with('/') => path.clone(),
+            (None, Some(path)) => format!("/{}", path),
+            (None, None) => "/".to_owned(),
+        };
+
+        if root.ends_with('/') {
+            root
+        } else {
+            root + "/"
+        }
+    }
+}
+
+/// Prism palettes by colour scheme, e.g. `nord` for `prism-nord.css`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CodeTheme {
+    pub light: String,
+    pub dark: String,
+}
+
+impl Default for CodeTheme {
+    fn default() -> Self {
+        CodeTheme {
+            light: "nord".to_owned(),
+            dark: "nord".to_owned(),
+        }
+    }
+}
+
+impl CodeTheme {
+    /// Stylesheets of the palettes, as theme assets
+    pub fn stylesheets(&self) -> CodeTheme {
+        CodeTheme {
+            light: format!("prism-{}.css", self.light),
+            dark: format!("prism-{}.css", self.dark),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TocFooter {
     pub link_url: String,
     pub content: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Language {
     pub id: String,
     pub name: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExternalScript {
+    pub src: String,
+    /// Subresource Integrity hash, e.g. `sha384-...`
+    pub integrity: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct LangLink {
     pub id: String,
     pub name: String,
 }
 
+/// Data of the book computed once per render, shared by the engine and the
+/// template through `HtmlContext`.
+#[derive(Debug, Default)]
+pub struct BookState {
+    /// Heading ids of the book
+    pub anchors: Anchors,
+    /// Heading ids of every edition, see `output.api.locale`
+    pub editions: Arc<Vec<(Locale, Anchors)>>,
+    /// Chapters of the edition shown in the default locale
+    pub untranslated: HashSet<PathBuf>,
+    /// Whether the chapters are streamed into a single page
+    pub single_page: bool,
+    /// Last commit of the chapters, with `output.api.git_metadata`
+    pub last_commits: HashMap<PathBuf, LastCommit>,
+    /// Front matter of the chapters, as written and parsed, by path
+    pub front_matter: HashMap<PathBuf, (BTreeMap<String, Value>, FrontMatter)>,
+    /// Table of contents, linked to the heading ids of the book
+    pub toc: Vec<TocItem>,
+}
+
+impl BookState {
+    pub fn new(ctx: &RenderContext, api_config: &ApiConfig, single_page: bool) -> Result<Self> {
+        let anchors = Anchors::from_book(
+            &ctx.book,
+            api_config.anchor_strategy(single_page),
+            single_page,
+        );
+        BookState::with_anchors(ctx, api_config, anchors, single_page)
+    }
+
+    /// State of a book whose heading ids are already known
+    pub fn with_anchors(
+        ctx: &RenderContext,
+        api_config: &ApiConfig,
+        anchors: Anchors,
+        single_page: bool,
+    ) -> Result<Self> {
+        let last_commits = if api_config.git_metadata {
+            git::last_commits(&ctx.book, &ctx.source_dir())
+        } else {
+            HashMap::new()
+        };
+
+        let mut front_matter = HashMap::new();
+        for item in ctx.book.iter() {
+            if let BookItem::Chapter(ref ch) = *item {
+                let (keys, parsed, _) = front_matter::parse(&ch.content)
+                    .chain_err(|| format!("Unable to read {}", ch.path.display()))?;
+                front_matter.insert(ch.path.clone(), (keys, parsed));
+            }
+        }
+
+        let toc = toc_items(&ctx.book.sections, 1, &front_matter, &anchors)?;
+
+        Ok(BookState {
+            anchors,
+            single_page,
+            last_commits,
+            front_matter,
+            toc,
+            ..Default::default()
+        })
+    }
+}
+
 #[derive(Default)]
 pub struct HtmlContext {
     // Current book item
     pub book_item: Option<BookItem>,
     // If the book item is the first one
     pub is_index: bool,
-    // Accumulate the content of all book items
-    pub full_content: String,
+    // Rendered content of the book item
+    pub content: String,
+    // Theme template used instead of `index.hbs`, from the front matter
+    pub layout: Option<String>,
+    // If the page is served for missing paths, it then links to the root
+    // with `site_url`
+    pub is_not_found: bool,
+    // Data of the whole book, shared by every page
+    pub book_state: Arc<BookState>,
 }
 
 // Prepare data for HTML rendering with Handlebar
 pub struct HtmlEngine {
-    data: serde_json::Map<String, serde_json::Value>,
+    data: TemplateData,
+    // Redirected anchors, as `page.html#id`
+    redirects: BTreeMap<String, String>,
+    // Public URL of the root of the book, if known
+    site_url: Option<String>,
+    // Image of the link previews, see `output.api.social_image`
+    social_image: Option<String>,
+    // Whether pages are described in JSON-LD
+    json_ld: bool,
+    // Whether the headings of the one page output are shifted
+    shift_headings: bool,
+    // See `output.api.edit_url_template`
+    edit_url_template: Option<String>,
+    // Neighbours and parents of the chapters, by path
+    navigation: HashMap<PathBuf, Navigation>,
+    // Paths of the chapters, in order
+    chapter_paths: Vec<PathBuf>,
+}
+
+/// Chapters around a chapter in `SUMMARY.md`, linked from the root.
+#[derive(Debug, Default)]
+struct Navigation {
+    previous: Option<NavLink>,
+    next: Option<NavLink>,
+    /// Parent chapters, starting from the top level
+    parents: Vec<NavLink>,
 }
 
 impl Engine<HtmlContext> for HtmlEngine {
-    type Output = serde_json::Map<String, serde_json::Value>;
+    type Output = TemplateData;
 
     fn name(&self) -> &str {
         "api"
@@ -64,98 +307,77 @@ impl Engine<HtmlContext> for HtmlEngine {
 
         let html_config = &ctx.config.html_config().unwrap_or_default();
 
-        let api_config: ApiConfig = match config.get_deserialized_opt("output.api") {
-            Ok(Some(config)) => Some(config),
-            _ => None,
-        }
-        .unwrap_or_default();
-
-        let mut data = serde_json::Map::new();
-
-        data.insert(
-            "language".to_owned(),
-            json!(config.book.language.clone().unwrap_or_default()),
-        );
-        data.insert(
-            "book_title".to_owned(),
-            json!(config.book.title.clone().unwrap_or_default()),
-        );
-        data.insert(
-            "description".to_owned(),
-            json!(config.book.description.clone().unwrap_or_default()),
-        );
-        data.insert("favicon".to_owned(), json!("favicon.png"));
+        let api_config = ApiConfig::from_context(ctx);
 
-        if let Some(ref livereload) = html_config.livereload_url {
-            data.insert("livereload".to_owned(), json!(livereload));
+        for script in &api_config.external_script {
+            if script.integrity.is_empty() {
+                return Err(
+                    format!("The external script {} has no integrity hash", script.src).into(),
+                );
+            }
         }
 
-        let mut lang_list = vec![];
-        let mut languages = vec![];
-
-        for lang in api_config.lang {
-            lang_list.push(lang.id.clone());
-
-            let l = if let Some(name) = lang.name {
-                LangLink {
-                    id: lang.id.clone(),
-                    name,
-                }
+        // Link previews need the absolute URL of the image
+        let social_image = match api_config.social_image {
+            Some(ref image) if api_config.site_url.is_none() && !has_scheme(image) => {
+                warn!("social_image {} is ignored, it needs a site_url", image);
+                None
+            }
+            ref image => image.clone(),
+        };
+
+        let site_url = api_config.site_url.as_ref().map(|_| api_config.site_root());
+
+        let language = config.book.language.clone().unwrap_or_default();
+        let strings = i18n::load(ctx)?;
+
+        let chapter_paths = book
+            .iter()
+            .filter_map(|item| match *item {
+                BookItem::Chapter(ref ch) => Some(ch.path.clone()),
+                BookItem::Separator => None,
+            })
+            .collect();
+
+        let mut data = TemplateData {
+            schema_version: SCHEMA_VERSION,
+            text_direction: if i18n::is_rtl(&language) {
+                "rtl"
             } else {
-                LangLink {
-                    id: lang.id.clone(),
-                    name: lang.id.clone(),
-                }
-            };
-            languages.push(l);
-        }
-
-        data.insert(
-            "lang_list".to_owned(),
-            json!(serde_json::to_string(&lang_list)?),
-        );
-        data.insert("languages".to_owned(), json!(&languages));
-
-        data.insert(
-            "toc_footer".to_owned(),
-            json!(api_config.toc_footer.unwrap_or_default()),
-        );
-
-        let mut chapters = vec![];
-
-        for item in book.iter() {
-            // Create the data to inject in the template
-            let mut chapter = BTreeMap::new();
-
-            match *item {
-                BookItem::Chapter(ref ch) => {
-                    if let Some(ref section) = ch.number {
-                        chapter.insert("section".to_owned(), json!(section.to_string()));
-                    }
-
-                    chapter.insert(
-                        "has_sub_items".to_owned(),
-                        json!((!ch.sub_items.is_empty()).to_string()),
-                    );
-
-                    chapter.insert("name".to_owned(), json!(ch.name));
-                    let path = ch
-                        .path
-                        .to_str()
-                        .chain_err(|| "Could not convert path to str")?;
-                    chapter.insert("path".to_owned(), json!(path));
-                }
-                BookItem::Separator => {
-                    chapter.insert("spacer".to_owned(), json!("_spacer_"));
-                }
+                "ltr"
             }
-
-            chapters.push(chapter);
-        }
-
-        data.insert("chapters".to_owned(), json!(chapters));
-
-        Ok(HtmlEngine { data })
+            .to_owned(),
+            language,
+            strings_json: json_attribute(&strings)?,
+            strings,
+            book_title: config.book.title.clone().unwrap_or_default(),
+            title: config.book.title.clone().unwrap_or_default(),
+            description: config.book.description.clone().unwrap_or_default(),
+            favicon: "favicon.png".to_owned(),
+            livereload: html_config.livereload_url.clone(),
+            self_contained: api_config.self_contained,
+            section_numbers: api_config.section_numbers,
+            search_headings: "h1, h2".to_owned(),
+            twitter_site: api_config.twitter_site.clone(),
+            base_url: api_config.site_root(),
+            code_theme: api_config.code_theme.stylesheets(),
+            toc_footer: api_config.toc_footer.unwrap_or_default(),
+            external_scripts: api_config.external_script,
+            ..Default::default()
+        };
+        add_languages(&mut data, api_config.lang)?;
+
+        Ok(HtmlEngine {
+            data,
+            redirects: anchors::normalize_redirects(&api_config.redirects),
+            site_url,
+            social_image,
+            json_ld: api_config.json_ld,
+            shift_headings: api_config.shift_headings,
+            edit_url_template: api_config.edit_url_template,
+            navigation: navigation(book),
+            chapter_paths,
+        })
     }
 
     fn process_chapter(
@@ -163,8 +385,7 @@ impl Engine<HtmlContext> for HtmlEngine {
         _ctx: &RenderContext,
         item: &mut HtmlContext,
     ) -> Result<Self::Output> {
-        // Clone the base data and apply changes based on chapter
-        let mut data = self.data.clone();
+        let book_state = item.book_state.clone();
 
         if let Some(BookItem::Chapter(ref ch)) = &item.book_item {
             // Update the context with data for this file
@@ -173,55 +394,512 @@ impl Engine<HtmlContext> for HtmlEngine {
                 .to_str()
                 .chain_err(|| "Could not convert path to str")?;
 
-            // Non-lexical lifetimes needed :'(
-            let title: String;
-            {
-                let book_title = data
-                    .get("book_title")
-                    .and_then(serde_json::Value::as_str)
-                    .unwrap_or("");
-
-                title = match book_title {
-                    "" => ch.name.clone(),
-                    _ => ch.name.clone() + " - " + book_title,
+            // Pages outside of the book are not parsed up front
+            let parsed;
+            let (keys, front_matter) = match book_state.front_matter.get(&ch.path) {
+                Some(front_matter) => front_matter,
+                None => {
+                    let (keys, front_matter, _) = front_matter::parse(&ch.content)?;
+                    parsed = (keys, front_matter);
+                    &parsed
                 }
-            }
+            };
 
-            data.insert("path".to_owned(), json!(path));
+            let name = front_matter.title.as_ref().unwrap_or(&ch.name);
+            let title = match self.data.book_title.as_str() {
+                "" => name.clone(),
+                book_title => name.clone() + " - " + book_title,
+            };
 
             let mut content = String::new();
-            let events = parser_from_str(&ch.content);
+            let events = parser_from_str(front_matter::strip(&ch.content));
+            let events = book_state.anchors.fix_events(events, &ch.path);
             html::push_html(&mut content, events);
 
-            item.full_content.push_str(&content);
-            data.insert("content".to_owned(), json!(content));
+            if let (true, Some(number)) = (self.data.section_numbers, &ch.number) {
+                content = number_first_heading(&content, &number.to_string());
+            }
+
+            if front_matter.search_exclude {
+                content = exclude_from_search(&content);
+            }
 
-            data.insert("chapter_title".to_owned(), json!(ch.name));
-            data.insert("title".to_owned(), json!(title));
-            data.insert(
-                "path_to_root".to_owned(),
-                json!(utils::fs::path_to_root(&ch.path)),
-            );
-            if let Some(ref section) = ch.number {
-                data.insert("section".to_owned(), json!(section.to_string()));
+            if item.is_not_found {
+                // Images of self-contained pages are inlined from the source
+                let images = !self.data.self_contained;
+                content = links_from_root(&content, &self.data.base_url, images);
             }
 
+            if book_state.untranslated.contains(&ch.path) {
+                let notice = format!(
+                    "\n<aside class=\"notice\" data-search-exclude>{}</aside>",
+                    html_escape(&self.data.strings["untranslated"])
+                );
+                content = after_first_heading(&content, &notice);
+            }
+
+            // The page served for missing paths has no source to edit
+            let edit_url = match self.edit_url_template {
+                Some(ref template) if !item.is_not_found => Some(edit_url(template, &ch.path)),
+                _ => None,
+            };
+            let last_commit = book_state.last_commits.get(&ch.path);
+
+            // The theme renders them around the content of a page, chapters
+            // streamed into one page carry their own
+            if book_state.single_page {
+                if let Some(ref url) = edit_url {
+                    content = link_edit_url(&content, url, &self.data.strings);
+                }
+                if let Some(commit) = last_commit {
+                    content.push_str(&last_updated(commit, &self.data.strings));
+                }
+            }
+
+            item.content = content.clone();
+            item.layout = front_matter.layout.clone();
+
+            // Chapters streamed into one page only need their content, the
+            // page itself is described by the data of the book
+            if book_state.single_page && !item.is_not_found {
+                return Ok(TemplateData::default());
+            }
+
+            // Clone the base data and apply changes based on chapter
+            let mut data = self.data.clone();
+            data.chapters = book_state.toc.clone();
+
+            let languages = front_matter.languages.iter().cloned();
+            add_languages(&mut data, languages.map(|tab| tab.into_language()))?;
+
+            // Describe the page with its first paragraph by default
+            let description = match front_matter.description {
+                Some(ref description) => Some(description.clone()),
+                None => first_paragraph(front_matter::strip(&ch.content)),
+            };
+            if let Some(description) = description {
+                data.description = description;
+            }
+
+            data.path = path.to_owned();
+            data.content = content;
+            data.chapter_title = name.clone();
+            data.title = title;
+            data.path_to_root = utils::fs::path_to_root(&ch.path);
+            data.section = ch.number.as_ref().map(|section| section.to_string());
+            data.front_matter = keys.clone();
+            data.last_commit = last_commit.cloned();
+            let strings = &data.strings;
+            let last_updated = last_commit.map(|commit| {
+                strings["last_updated"]
+                    .replace("{date}", &commit.date)
+                    .replace("{author}", &commit.author)
+            });
+            data.last_updated = last_updated;
+            data.edit_url = edit_url;
+
             if item.is_index {
-                data.insert("path".to_owned(), json!("index.md"));
-                data.insert("path_to_root".to_owned(), json!(""));
-                data.insert("is_index".to_owned(), json!("true"));
+                data.path = "index.md".to_owned();
+                data.path_to_root = "".to_owned();
+                data.is_index = true;
+            }
+
+            if item.is_not_found {
+                data.path_to_root = self.data.base_url.clone();
+            }
+
+            if let Some(navigation) = self.navigation.get(&ch.path) {
+                let path_to_root = data.path_to_root.clone();
+                let link_from_page = |link: &NavLink| NavLink {
+                    name: link.name.clone(),
+                    link: format!("{}{}", path_to_root, link.link),
+                };
+
+                data.previous = navigation.previous.as_ref().map(link_from_page);
+                data.next = navigation.next.as_ref().map(link_from_page);
+                data.breadcrumbs = navigation.parents.iter().map(link_from_page).collect();
+                data.parent = data.breadcrumbs.last().cloned();
+            }
+
+            let page = anchors::page_name(Path::new(&data.path));
+            self.add_locales(&book_state, &mut data, &page, &[&ch.path])?;
+            data.anchor_redirects =
+                anchors::page_redirects(&self.redirects, &page, &data.path_to_root)?;
+
+            // The page served for missing paths has no URL of its own
+            if !item.is_not_found {
+                data.canonical_url = self
+                    .site_url
+                    .as_ref()
+                    .map(|site_url| sitemap::page_url(site_url, &page));
             }
+
+            self.add_social_metadata(&mut data)?;
+
+            return Ok(data);
         }
 
-        Ok(data)
+        Ok(TemplateData::default())
     }
 
     fn finalize_book(&self, _ctx: &RenderContext, item: &mut HtmlContext) -> Result<Self::Output> {
+        self.book_data(&item.book_state)
+    }
+}
+
+impl HtmlEngine {
+    /// Data of the page gathering the whole book, without content as the
+    /// chapters are written one by one. It has the language tabs of all the
+    /// chapters.
+    fn book_data(&self, book_state: &BookState) -> Result<TemplateData> {
         let mut data = self.data.clone();
-        data.insert("content".to_owned(), json!(item.full_content));
-        data.insert("path".to_owned(), json!("index.md"));
-        data.insert("path_to_root".to_owned(), json!(""));
-        data.insert("is_index".to_owned(), json!("true"));
+        data.chapters = book_state.toc.clone();
+        data.path = "index.md".to_owned();
+        data.path_to_root = "".to_owned();
+        data.is_index = true;
+        data.anchor_redirects = anchors::page_redirects(&self.redirects, "index.html", "")?;
+
+        // Nested chapters start with deeper headings
+        if book_state.single_page && self.shift_headings {
+            data.search_headings = "h1, h2, h3, h4, h5, h6".to_owned();
+        }
+        data.canonical_url = self
+            .site_url
+            .as_ref()
+            .map(|site_url| sitemap::page_url(site_url, "index.html"));
+
+        let chapter_paths: Vec<_> = self.chapter_paths.iter().collect();
+        self.add_locales(book_state, &mut data, "index.html", &chapter_paths)?;
+
+        let mut paths: Vec<_> = book_state.front_matter.keys().collect();
+        paths.sort();
+        for path in paths {
+            let languages = book_state.front_matter[path].1.languages.iter().cloned();
+            add_languages(&mut data, languages.map(|tab| tab.into_language()))?;
+        }
+
+        self.add_social_metadata(&mut data)?;
         Ok(data)
     }
+
+    /// Link the page to its equivalent in every edition, with the heading ids
+    /// of its chapters to keep the current anchor.
+    fn add_locales(
+        &self,
+        book_state: &BookState,
+        data: &mut TemplateData,
+        page: &str,
+        chapters: &[&PathBuf],
+    ) -> Result<()> {
+        let mut anchors = BTreeMap::new();
+
+        for (locale, edition_anchors) in book_state.editions.iter() {
+            data.locales.push(LocaleLink {
+                id: locale.id.clone(),
+                name: locale.name().to_owned(),
+                link: format!("{}../{}/{}", data.path_to_root, locale.id, page),
+                is_current: locale.id == data.language,
+            });
+
+            let ids: Vec<_> = chapters
+                .iter()
+                .map(|path| edition_anchors.ids(path))
+                .collect();
+            anchors.insert(&locale.id, ids);
+        }
+
+        if !book_state.editions.is_empty() {
+            data.locale_anchors = json_attribute(&anchors)?;
+        }
+        Ok(())
+    }
+
+    /// Image and JSON-LD description of the page, for the link previews and
+    /// search engines.
+    fn add_social_metadata(&self, data: &mut TemplateData) -> Result<()> {
+        data.social_image = self.social_image.as_ref().map(|image| {
+            if has_scheme(image) {
+                image.clone()
+            } else {
+                let root = self.site_url.as_deref().unwrap_or_default();
+                format!("{}{}", root, image.trim_start_matches('/'))
+            }
+        });
+
+        if self.json_ld {
+            let mut article = serde_json::json!({
+                "@context": "https://schema.org",
+                "@type": "TechArticle",
+                "headline": data.title,
+                "description": data.description,
+            });
+
+            if let Some(ref url) = data.canonical_url {
+                article["url"] = url.as_str().into();
+            }
+            if let Some(ref image) = data.social_image {
+                article["image"] = image.as_str().into();
+            }
+            if !data.language.is_empty() {
+                article["inLanguage"] = data.language.as_str().into();
+            }
+
+            // The block is written in a script element
+            let json = serde_json::to_string(&article)?.replace("</", "<\\/");
+            data.json_ld = Some(json);
+        }
+
+        Ok(())
+    }
+}
+
+/// Add the languages missing from the language selector.
+fn add_languages<I>(data: &mut TemplateData, languages: I) -> Result<()>
+where
+    I: IntoIterator<Item = Language>,
+{
+    for lang in languages {
+        if data.languages.iter().any(|link| link.id == lang.id) {
+            continue;
+        }
+
+        let Language { id, name } = lang;
+        let name = name.unwrap_or_else(|| id.clone());
+        data.languages.push(LangLink { id, name });
+    }
+
+    let lang_list: Vec<_> = data.languages.iter().map(|link| &link.id).collect();
+    data.lang_list = serde_json::to_string(&lang_list)?;
+    Ok(())
+}
+
+/// Text of the first paragraph of a chapter, shortened to fit in a
+/// description.
+fn first_paragraph(markdown: &str) -> Option<String> {
+    const MAX_LENGTH: usize = 160;
+
+    let mut text = String::new();
+    let mut in_paragraph = false;
+
+    for event in utils::new_cmark_parser(markdown) {
+        match event {
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::End(Tag::Paragraph) if !text.trim().is_empty() => break,
+            Event::End(Tag::Paragraph) => in_paragraph = false,
+            Event::Text(ref part) | Event::Code(ref part) if in_paragraph => text.push_str(part),
+            Event::SoftBreak | Event::HardBreak if in_paragraph => text.push(' '),
+            _ => {}
+        }
+    }
+
+    let words: Vec<_> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut description = String::new();
+    for word in words {
+        if description.chars().count() + word.chars().count() + 1 > MAX_LENGTH {
+            description.push('…');
+            break;
+        }
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(word);
+    }
+
+    Some(description)
+}
+
+/// Mark the headings so that `api.js` leaves them out of the search index.
+fn exclude_from_search(html: &str) -> String {
+    lazy_static! {
+        static ref HEADING: Regex = Regex::new(r"<h([1-6])([ >])").unwrap();
+    }
+
+    HEADING
+        .replace_all(html, "<h$1 data-search-exclude$2")
+        .into_owned()
+}
+
+/// Resolve the relative links of a page served at any path from the root of
+/// the book, and the sources of its images unless `images` is false.
+fn links_from_root(html: &str, root: &str, images: bool) -> String {
+    lazy_static! {
+        static ref LINK: Regex =
+            Regex::new(r#"(<(?:a|img) [^>]*?(?:src|href)=")([^"]+?)""#).unwrap();
+    }
+
+    LINK.replace_all(html, |caps: &Captures<'_>| {
+        if is_absolute(&caps[2]) || (!images && caps[1].starts_with("<img")) {
+            caps[0].to_owned()
+        } else {
+            format!("{}{}{}\"", &caps[1], root, &caps[2])
+        }
+    })
+    .into_owned()
+}
+
+/// Give the section number to the first heading of a chapter, displayed by
+/// the stylesheet.
+fn number_first_heading(html: &str, section: &str) -> String {
+    lazy_static! {
+        static ref HEADING: Regex = Regex::new(r"<h([1-6])([ >])").unwrap();
+    }
+
+    let replacement = format!(r#"<h$1 data-section="{}"$2"#, section);
+    HEADING.replace(html, replacement.as_str()).into_owned()
+}
+
+/// URL editing the source of a chapter, from `output.api.edit_url_template`.
+fn edit_url(template: &str, path: &Path) -> String {
+    let path = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    template.replace("{path}", &path)
+}
+
+/// Link the source of a chapter right after its first heading, left out of
+/// the search.
+fn link_edit_url(html: &str, url: &str, strings: &i18n::Strings) -> String {
+    let link = format!(
+        "\n<p class=\"edit-link\" data-search-exclude><a href=\"{}\">{}</a></p>",
+        html_escape(url),
+        html_escape(&strings["edit_chapter"]),
+    );
+    after_first_heading(html, &link)
+}
+
+/// Insert `snippet` after the first heading of a chapter, or at its start.
+fn after_first_heading(html: &str, snippet: &str) -> String {
+    lazy_static! {
+        static ref HEADING_END: Regex = Regex::new(r"</h[1-6]>").unwrap();
+    }
+
+    match HEADING_END.find(html) {
+        Some(heading) => format!(
+            "{}{}{}",
+            &html[..heading.end()],
+            snippet,
+            &html[heading.end()..]
+        ),
+        None => format!("{}{}", snippet, html),
+    }
+}
+
+/// Line closing a chapter with its last commit, left out of the search.
+fn last_updated(commit: &LastCommit, strings: &i18n::Strings) -> String {
+    let date = format!(
+        "<time datetime=\"{date}\">{date}</time>",
+        date = commit.date
+    );
+    let text = html_escape(&strings["last_updated"])
+        .replace("{date}", &date)
+        .replace("{author}", &html_escape(&commit.author));
+
+    format!(
+        "\n<p class=\"last-updated\" data-search-exclude>{} (<code>{}</code>)</p>\n",
+        text, commit.hash
+    )
+}
+
+/// Previous, next and parent chapters of every chapter, following the order
+/// of `SUMMARY.md`. The first chapter is the index of the book.
+fn navigation(book: &Book) -> HashMap<PathBuf, Navigation> {
+    fn walk(
+        items: &[BookItem],
+        parents: &mut Vec<NavLink>,
+        pages: &mut Vec<(PathBuf, NavLink, Vec<NavLink>)>,
+    ) {
+        for item in items {
+            if let BookItem::Chapter(ref ch) = *item {
+                let link = if pages.is_empty() {
+                    "index.html".to_owned()
+                } else {
+                    anchors::page_name(&ch.path)
+                };
+                let link = NavLink {
+                    name: ch.name.clone(),
+                    link,
+                };
+                pages.push((ch.path.clone(), link.clone(), parents.clone()));
+
+                parents.push(link);
+                walk(&ch.sub_items, parents, pages);
+                parents.pop();
+            }
+        }
+    }
+
+    let mut pages = vec![];
+    walk(&book.sections, &mut vec![], &mut pages);
+
+    let mut navigation = HashMap::new();
+    for (index, (path, _, parents)) in pages.iter().enumerate() {
+        let previous = index.checked_sub(1).and_then(|index| pages.get(index));
+        let next = pages.get(index + 1);
+
+        navigation.insert(
+            path.clone(),
+            Navigation {
+                previous: previous.map(|(_, link, _)| link.clone()),
+                next: next.map(|(_, link, _)| link.clone()),
+                parents: parents.clone(),
+            },
+        );
+    }
+
+    navigation
+}
+
+/// Build the table of contents tree from the book items.
+fn toc_items(
+    items: &[BookItem],
+    level: usize,
+    front_matter: &HashMap<PathBuf, (BTreeMap<String, Value>, FrontMatter)>,
+    anchors: &Anchors,
+) -> Result<Vec<TocItem>> {
+    let mut toc = vec![];
+
+    for item in items {
+        match *item {
+            BookItem::Chapter(ref ch) => {
+                let path = ch
+                    .path
+                    .to_str()
+                    .chain_err(|| "Could not convert path to str")?;
+
+                // Chapters without heading keep the id of their name
+                let anchor = match anchors.first(&ch.path) {
+                    Some(anchor) => anchor.to_owned(),
+                    None => utils::normalize_id(&ch.name),
+                };
+
+                toc.push(TocItem {
+                    name: ch.name.clone(),
+                    path: path.to_owned(),
+                    anchor,
+                    section: ch.number.as_ref().map(|section| section.to_string()),
+                    level,
+                    is_separator: false,
+                    is_hidden: front_matter[&ch.path].1.hide_toc,
+                    has_sub_items: !ch.sub_items.is_empty(),
+                    sub_items: toc_items(&ch.sub_items, level + 1, front_matter, anchors)?,
+                });
+            }
+            BookItem::Separator => {
+                toc.push(TocItem {
+                    level,
+                    is_separator: true,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    Ok(toc)
 }
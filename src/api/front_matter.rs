@@ -0,0 +1,188 @@
+//! Metadata given at the top of a chapter, either in YAML between `---` lines
+//! or in TOML between `+++` lines.
+//!
+//! ```markdown
+//! ---
+//! title: Users
+//! description: Manage the users of an account
+//! languages: [ruby, { id: go, name: Go }]
+//! search_exclude: false
+//! hide_toc: false
+//! layout: wide
+//! ---
+//!
+//! # Users
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::api::engine::Language;
+
+use mdbook::errors::Result;
+use mdbook::errors::ResultExt;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct FrontMatter {
+    /// Title of the page, instead of the chapter name
+    pub title: Option<String>,
+    /// Description of the page, instead of the book description
+    pub description: Option<String>,
+    /// Language tabs added to the ones of `output.api.lang`
+    pub languages: Vec<LanguageTab>,
+    /// Exclude the headings of the chapter from the search
+    pub search_exclude: bool,
+    /// Hide the chapter from the table of contents
+    pub hide_toc: bool,
+    /// Name of the theme template used for the page, without `.hbs`
+    pub layout: Option<String>,
+}
+
+/// A language tab, given by id or as a table like `output.api.lang`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum LanguageTab {
+    Id(String),
+    Language(Language),
+}
+
+impl LanguageTab {
+    pub fn into_language(self) -> Language {
+        match self {
+            LanguageTab::Id(id) => Language { id, name: None },
+            LanguageTab::Language(language) => language,
+        }
+    }
+}
+
+/// Split the front matter from the content of a chapter, returning all its
+/// keys as they were written, its known fields and the remaining content.
+pub fn parse(content: &str) -> Result<(BTreeMap<String, Value>, FrontMatter, &str)> {
+    let (keys, rest) = match split(content) {
+        Some((keys, rest)) => (keys?, rest),
+        None => return Ok((BTreeMap::new(), FrontMatter::default(), content)),
+    };
+
+    let front_matter = serde_json::from_value(Value::Object(keys.clone().into_iter().collect()))
+        .chain_err(|| "Invalid front matter")?;
+
+    Ok((keys, front_matter, rest))
+}
+
+/// The content of a chapter without its front matter.
+pub fn strip(content: &str) -> &str {
+    split(content).map_or(content, |(_, rest)| rest)
+}
+
+/// The keys of the front matter and the remaining content. A block between
+/// `---` lines is also valid Markdown, with thematic breaks, so it is only
+/// front matter when it is a YAML mapping.
+fn split(content: &str) -> Option<(Result<BTreeMap<String, Value>>, &str)> {
+    let delimiter = match content.lines().next()?.trim_end() {
+        "---" => "---",
+        "+++" => "+++",
+        _ => return None,
+    };
+
+    let start = content.find('\n')? + 1;
+    let mut offset = start;
+
+    for line in content[start..].split_inclusive('\n') {
+        if line.trim_end() == delimiter {
+            let raw = &content[start..offset];
+            let rest = &content[offset + line.len()..];
+
+            return match delimiter {
+                "---" => Some((Ok(serde_yaml::from_str(raw).ok()?), rest)),
+                _ => Some((
+                    toml::from_str(raw).chain_err(|| "Invalid TOML front matter"),
+                    rest,
+                )),
+            };
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_front_matter() {
+        let content = "---\ntitle: Users\nsearch_exclude: true\n---\n# Users\n";
+        let (keys, front_matter, rest) = parse(content).unwrap();
+
+        assert_eq!(keys["title"], "Users");
+        assert_eq!(front_matter.title.as_deref(), Some("Users"));
+        assert!(front_matter.search_exclude);
+        assert_eq!(rest, "# Users\n");
+        assert_eq!(strip(content), "# Users\n");
+    }
+
+    #[test]
+    fn toml_front_matter() {
+        let content = "+++\ntitle = \"Users\"\nlayout = \"wide\"\n+++\n# Users\n";
+        let (keys, front_matter, rest) = parse(content).unwrap();
+
+        assert_eq!(keys.len(), 2);
+        assert_eq!(front_matter.layout.as_deref(), Some("wide"));
+        assert_eq!(rest, "# Users\n");
+    }
+
+    #[test]
+    fn crlf_front_matter() {
+        let content = "---\r\ntitle: Users\r\n---\r\n# Users\r\n";
+        let (_, front_matter, rest) = parse(content).unwrap();
+
+        assert_eq!(front_matter.title.as_deref(), Some("Users"));
+        assert_eq!(rest, "# Users\r\n");
+    }
+
+    #[test]
+    fn empty_front_matter() {
+        let content = "+++\n+++\n# Users\n";
+        let (keys, _, rest) = parse(content).unwrap();
+
+        assert!(keys.is_empty());
+        assert_eq!(rest, "# Users\n");
+
+        // Two thematic breaks
+        let content = "---\n---\n# Users\n";
+        assert_eq!(parse(content).unwrap().2, content);
+        assert_eq!(strip(content), content);
+    }
+
+    #[test]
+    fn unterminated_front_matter() {
+        let content = "---\ntitle: Users\n# Users\n";
+        let (keys, _, rest) = parse(content).unwrap();
+
+        assert!(keys.is_empty());
+        assert_eq!(rest, content);
+        assert_eq!(
+            strip("+++\ntitle = \"Users\"\n"),
+            "+++\ntitle = \"Users\"\n"
+        );
+    }
+
+    #[test]
+    fn thematic_breaks_are_content() {
+        let content = "---\nSome text\n\n---\n# Users\n";
+        let (keys, _, rest) = parse(content).unwrap();
+
+        assert!(keys.is_empty());
+        assert_eq!(rest, content);
+        assert_eq!(strip(content), content);
+    }
+
+    #[test]
+    fn invalid_front_matter() {
+        assert!(parse("+++\ntitle = \n+++\n").is_err());
+        assert!(parse("---\ntitle: [Users]\n---\n").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Last commit of the chapters, read from the local git repository of the
+//! book, see `output.api.git_metadata`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use mdbook::book::{Book, BookItem};
+
+/// Last commit changing a chapter.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LastCommit {
+    /// Date of the commit, e.g. `2020-05-17`
+    pub date: String,
+    pub author: String,
+    /// Abbreviated hash of the commit
+    pub hash: String,
+}
+
+/// Last commit of every chapter tracked in the repository of `source_dir`,
+/// by path. The book doesn't need to be in a repository, chapters are then
+/// left without commit.
+pub fn last_commits(book: &Book, source_dir: &Path) -> HashMap<PathBuf, LastCommit> {
+    let mut commits = HashMap::new();
+
+    if head(source_dir).is_none() {
+        warn!("{} is not in a git repository", source_dir.display());
+        return commits;
+    }
+
+    for item in book.iter() {
+        if let BookItem::Chapter(ref ch) = *item {
+            if let Some(commit) = last_commit(source_dir, &ch.path) {
+                commits.insert(ch.path.clone(), commit);
+            }
+        }
+    }
+
+    commits
+}
+
+/// Hash of the current commit of the repository of `source_dir`, if any.
+pub fn head(source_dir: &Path) -> Option<String> {
+    git(source_dir, &["rev-parse", "HEAD"])
+}
+
+fn last_commit(source_dir: &Path, path: &Path) -> Option<LastCommit> {
+    let path = path.to_str()?;
+    let log = git(
+        source_dir,
+        &["log", "-1", "--format=%cI%x1f%an%x1f%h", "--", path],
+    )?;
+
+    let mut fields = log.split('\x1f');
+    let date = fields.next()?.get(..10)?.to_owned();
+    let author = fields.next()?.to_owned();
+    let hash = fields.next()?.to_owned();
+
+    Some(LastCommit { date, author, hash })
+}
+
+/// Output of a git command run in `dir`, `None` when it fails or is empty.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    match stdout.trim() {
+        "" => None,
+        stdout => Some(stdout.to_owned()),
+    }
+}
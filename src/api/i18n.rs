@@ -0,0 +1,86 @@
+//! Text of the user interface in the language of the book, `book.language`.
+//!
+//! The English strings are overlaid with the built-in translation of the
+//! language, then with `i18n/<language>.toml` from the theme folder, then
+//! with the `output.api.i18n` table:
+//!
+//! ```toml
+//! [output.api.i18n]
+//! sign_up = "Request an API key"
+//! ```
+
+use crate::api::engine::ApiConfig;
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use mdbook::errors::Result;
+use mdbook::errors::ResultExt;
+use mdbook::renderer::RenderContext;
+
+/// Text of the user interface, by key, e.g. `search`.
+pub type Strings = BTreeMap<String, String>;
+
+static BUILT_IN: &[(&str, &str)] = &[
+    ("en", include_str!("../../theme/i18n/en.toml")),
+    ("de", include_str!("../../theme/i18n/de.toml")),
+    ("es", include_str!("../../theme/i18n/es.toml")),
+    ("fr", include_str!("../../theme/i18n/fr.toml")),
+    ("ja", include_str!("../../theme/i18n/ja.toml")),
+];
+
+/// Languages written from right to left, by primary subtag.
+static RTL: &[&str] = &[
+    "ar", "dv", "fa", "he", "ks", "ku", "ps", "sd", "ug", "ur", "yi",
+];
+
+/// Strings of the language of the book, English ones filling the gaps.
+pub fn load(ctx: &RenderContext) -> Result<Strings> {
+    let api_config = ApiConfig::from_context(ctx);
+    let language = ctx.config.book.language.clone().unwrap_or_default();
+    let theme_dir = api_config.theme_dir(&ctx.root).join("i18n");
+
+    // From the most generic language to the most specific, e.g. `pt` then
+    // `pt-BR`
+    let mut tags = vec!["en".to_owned()];
+    let mut tag = String::new();
+    for subtag in language
+        .split(['-', '_'])
+        .filter(|subtag| !subtag.is_empty())
+    {
+        if !tag.is_empty() {
+            tag.push('-');
+        }
+        tag.push_str(subtag);
+        if tag != "en" {
+            tags.push(tag.clone());
+        }
+    }
+
+    let mut strings = Strings::new();
+    for tag in &tags {
+        if let Some(&(_, built_in)) = BUILT_IN.iter().find(|&&(id, _)| id == tag) {
+            strings.extend(parse(built_in, tag)?);
+        }
+
+        let path = theme_dir.join(format!("{}.toml", tag));
+        if path.exists() {
+            let content = fs::read_to_string(&path)
+                .chain_err(|| format!("Unable to read {}", path.display()))?;
+            strings.extend(parse(&content, &path.display().to_string())?);
+        }
+    }
+
+    strings.extend(api_config.i18n);
+    Ok(strings)
+}
+
+/// Whether a language, e.g. `ar-EG`, is written from right to left.
+pub fn is_rtl(language: &str) -> bool {
+    let primary = language.split(['-', '_']).next().unwrap_or_default();
+    RTL.contains(&primary.to_lowercase().as_str())
+}
+
+fn parse(content: &str, name: &str) -> Result<Strings> {
+    toml::from_str(content).chain_err(|| format!("Invalid strings in {}", name))
+}
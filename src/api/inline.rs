@@ -0,0 +1,162 @@
+//! Inline the assets referenced by a rendered page, producing a single HTML
+//! file which does not need any other file or external request.
+
+use crate::api::util::resolve_path;
+
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::path::Path;
+
+use mdbook::errors::Result;
+
+/// Replace stylesheets, scripts, images and fonts referenced by `html` with
+/// their content. References are resolved against the theme `assets` first,
+/// then against the book source directory `src_dir`. `page` is the path of the
+/// page relative to the root of the output.
+pub fn inline_assets(
+    html: &str,
+    page: &Path,
+    assets: &HashMap<String, Vec<u8>>,
+    src_dir: &Path,
+) -> Result<String> {
+    lazy_static! {
+        static ref STYLESHEET: Regex = Regex::new(
+            r#"<link (?:rel="stylesheet" href="([^"]+)"|href="([^"]+)" rel="stylesheet")([^>]*?)/?>"#
+        )
+        .unwrap();
+        static ref SCRIPT: Regex =
+            Regex::new(r#"<script src="([^"]+)"[^>]*>\s*</script>"#).unwrap();
+        static ref SOURCE: Regex =
+            Regex::new(r##"(<(?:img|link) [^>]*?(?:src|href)=")([^"#]+)""##).unwrap();
+        static ref EXTERNAL: Regex =
+            Regex::new(r#"<(?:script|link|img) [^>]*?(?:src|href)="((?:https?:)?//[^"]+)""#)
+                .unwrap();
+    }
+
+    let base = page.parent().unwrap_or_else(|| Path::new(""));
+    let resolver = Resolver { assets, src_dir };
+
+    let html = STYLESHEET.replace_all(html, |caps: &Captures<'_>| {
+        let link = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+        let path = resolve_path(base, link);
+
+        match resolver.load(&path) {
+            Some(css) => {
+                let css = String::from_utf8_lossy(css);
+                let css_base = path.parent().unwrap_or_else(|| Path::new(""));
+                // Other attributes, e.g. `media`, still apply to the style
+                format!(
+                    "<style{}>{}</style>",
+                    caps[3].trim_end(),
+                    inline_css_urls(&css, css_base, &resolver)
+                )
+            }
+            None => caps[0].to_owned(),
+        }
+    });
+
+    let html = SCRIPT.replace_all(&html, |caps: &Captures<'_>| {
+        let path = resolve_path(base, &caps[1]);
+
+        match resolver.load(&path) {
+            Some(js) => format!(
+                "<script>{}</script>",
+                String::from_utf8_lossy(js).replace("</script", "<\\/script")
+            ),
+            None => caps[0].to_owned(),
+        }
+    });
+
+    let html = SOURCE.replace_all(&html, |caps: &Captures<'_>| {
+        let path = resolve_path(base, &caps[2]);
+
+        match resolver.data_uri(&path) {
+            Some(uri) => format!("{}{}\"", &caps[1], uri),
+            None => caps[0].to_owned(),
+        }
+    });
+
+    for caps in EXTERNAL.captures_iter(&html) {
+        warn!(
+            "{} still references the external resource {}",
+            page.display(),
+            &caps[1]
+        );
+    }
+
+    Ok(html.into_owned())
+}
+
+/// Replace `url(...)` references of a stylesheet located in `base` with data
+/// URIs.
+fn inline_css_urls(css: &str, base: &Path, resolver: &Resolver<'_>) -> String {
+    lazy_static! {
+        static ref CSS_URL: Regex = Regex::new(r#"url\(['"]?([^'")]+)['"]?\)"#).unwrap();
+    }
+
+    CSS_URL
+        .replace_all(css, |caps: &Captures<'_>| {
+            let path = resolve_path(base, &caps[1]);
+
+            match resolver.data_uri(&path) {
+                Some(uri) => format!("url({})", uri),
+                None => caps[0].to_owned(),
+            }
+        })
+        .into_owned()
+}
+
+struct Resolver<'a> {
+    assets: &'a HashMap<String, Vec<u8>>,
+    src_dir: &'a Path,
+}
+
+impl<'a> Resolver<'a> {
+    fn load(&self, path: &Path) -> Option<&'a [u8]> {
+        let name = path.to_str()?.replace('\\', "/");
+        self.assets.get(&name).map(Vec::as_slice)
+    }
+
+    fn data_uri(&self, path: &Path) -> Option<String> {
+        if path.as_os_str().is_empty() {
+            return None;
+        }
+
+        let mime = mime_type(path)?;
+
+        let encoded = match self.load(path) {
+            Some(content) => base64::encode(content),
+            None => {
+                let filename = self.src_dir.join(path);
+                match std::fs::read(&filename) {
+                    Ok(content) => base64::encode(&content),
+                    Err(_) => {
+                        warn!("Unable to inline {}", filename.display());
+                        return None;
+                    }
+                }
+            }
+        };
+
+        Some(format!("data:{};base64,{}", mime, encoded))
+    }
+}
+
+fn mime_type(path: &Path) -> Option<&'static str> {
+    let mime = match path.extension()?.to_str()? {
+        "css" => "text/css",
+        "js" => "text/javascript",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "ico" => "image/x-icon",
+        "webp" => "image/webp",
+        "eot" => "application/vnd.ms-fontobject",
+        "ttf" => "font/ttf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        _ => return None,
+    };
+    Some(mime)
+}
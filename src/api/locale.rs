@@ -0,0 +1,286 @@
+//! Editions of the book in several human languages, see `output.api.locale`.
+//!
+//! The source folder holds the default locale, the first one listed, and the
+//! other locales are the folders next to it:
+//!
+//! ```text
+//! src/en/SUMMARY.md   book.src = "src/en"
+//! src/ja/SUMMARY.md
+//! src/ja/users/list.md
+//! ```
+//!
+//! Every edition follows the structure of the default locale, with the
+//! chapter names of its own `SUMMARY.md`. Chapters which are not translated
+//! keep the content of the default locale. The translated chapters go through
+//! the preprocessors of the book like the default locale does with
+//! `mdbook build`. Each edition is written to its own folder of the output,
+//! e.g. `book/ja/`.
+
+use crate::api::engine::ApiConfig;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mdbook::book::{self, Book, BookItem, SummaryItem};
+use mdbook::errors::Result;
+use mdbook::errors::ResultExt;
+use mdbook::preprocess::{
+    CmdPreprocessor, IndexPreprocessor, LinkPreprocessor, Preprocessor, PreprocessorContext,
+};
+use mdbook::renderer::RenderContext;
+use mdbook::Config;
+use toml::Value;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Locale {
+    /// Language id, also the name of the source and output folders
+    pub id: String,
+    /// Display name, optional
+    pub name: Option<String>,
+}
+
+impl Locale {
+    pub fn name(&self) -> &str {
+        self.name.as_ref().unwrap_or(&self.id)
+    }
+}
+
+/// An edition of the book in one locale.
+pub struct Edition {
+    pub locale: Locale,
+    pub book: Book,
+    /// Chapters shown with the content of the default locale
+    pub untranslated: HashSet<PathBuf>,
+}
+
+/// Folder of the sources of a locale, next to the source folder.
+pub fn source_dir(ctx: &RenderContext, id: &str) -> PathBuf {
+    let source_dir = ctx.source_dir();
+    source_dir.parent().unwrap_or(&source_dir).join(id)
+}
+
+/// Check that the source folder is the one of the default locale.
+pub fn check(ctx: &RenderContext, locales: &[Locale]) -> Result<()> {
+    if let Some(default) = locales.first() {
+        if ctx.source_dir() != source_dir(ctx, &default.id) {
+            return Err(format!(
+                "book.src must be the folder of the default locale {}, e.g. src/{}",
+                default.id, default.id
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Every edition of the book, from the context of the default locale and the
+/// name of the renderer the preprocessors run for.
+pub fn editions(ctx: &RenderContext, renderer: &str) -> Result<Vec<Edition>> {
+    let locales = ApiConfig::from_context(ctx).locale;
+    let default = match locales.first() {
+        Some(default) => default,
+        None => return Ok(vec![]),
+    };
+
+    let mut editions = vec![Edition {
+        locale: default.clone(),
+        book: ctx.book.clone(),
+        untranslated: HashSet::new(),
+    }];
+
+    for locale in &locales[1..] {
+        let (book, untranslated) = translate(&ctx.book, &source_dir(ctx, &locale.id))
+            .and_then(|(book, untranslated)| {
+                let config = config(ctx, locale);
+                let book = preprocess(ctx, config, renderer, book)?;
+                Ok((restore(book, &ctx.book, &untranslated), untranslated))
+            })
+            .chain_err(|| format!("Unable to load the {} edition", locale.id))?;
+
+        editions.push(Edition {
+            locale: locale.clone(),
+            book,
+            untranslated,
+        });
+    }
+
+    Ok(editions)
+}
+
+/// Configuration of the book in a locale, read from its own folder.
+fn config(ctx: &RenderContext, locale: &Locale) -> Config {
+    let mut config = ctx.config.clone();
+    config.book.language = Some(locale.id.clone());
+    config.book.src = config
+        .book
+        .src
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(&locale.id);
+    config
+}
+
+/// Render context of the edition of a locale, written to its own folder.
+pub fn context(ctx: &RenderContext, edition: Edition) -> Result<RenderContext> {
+    let api_config = ApiConfig::from_context(ctx);
+    let id = &edition.locale.id;
+    let mut config = config(ctx, &edition.locale);
+
+    // The pages of the edition are served from its folder
+    if let Some(site_url) = api_config.site_url.as_ref().map(|_| api_config.site_root()) {
+        config.set("output.api.site_url", format!("{}{}/", site_url, id))?;
+    } else {
+        config.set(
+            "output.api.base_path",
+            format!("{}{}/", api_config.site_root(), id),
+        )?;
+    }
+
+    Ok(RenderContext::new(
+        &ctx.root,
+        edition.book,
+        config,
+        ctx.destination.join(id),
+    ))
+}
+
+/// The default book with the chapters and names translated in `dir`.
+fn translate(default_book: &Book, dir: &Path) -> Result<(Book, HashSet<PathBuf>)> {
+    let names = match fs::read_to_string(dir.join("SUMMARY.md")) {
+        Ok(summary) => summary_names(&book::parse_summary(&summary)?),
+        Err(_) => HashMap::new(),
+    };
+
+    let mut book = default_book.clone();
+    let mut untranslated = HashSet::new();
+
+    book.for_each_mut(|item| {
+        if let BookItem::Chapter(ref mut ch) = *item {
+            if let Some(name) = names.get(&ch.path) {
+                ch.name = name.clone();
+            }
+
+            // The content of the default locale is already preprocessed
+            match fs::read_to_string(dir.join(&ch.path)) {
+                Ok(content) => ch.content = content,
+                Err(_) => {
+                    ch.content.clear();
+                    untranslated.insert(ch.path.clone());
+                }
+            }
+        }
+    });
+
+    Ok((book, untranslated))
+}
+
+/// Put back the content of the default locale in the untranslated chapters.
+fn restore(mut book: Book, default_book: &Book, untranslated: &HashSet<PathBuf>) -> Book {
+    let contents: HashMap<_, _> = default_book
+        .iter()
+        .filter_map(|item| match *item {
+            BookItem::Chapter(ref ch) if untranslated.contains(&ch.path) => {
+                Some((&ch.path, &ch.content))
+            }
+            _ => None,
+        })
+        .collect();
+
+    book.for_each_mut(|item| {
+        if let BookItem::Chapter(ref mut ch) = *item {
+            if let Some(content) = contents.get(&ch.path) {
+                ch.content = (*content).clone();
+            }
+        }
+    });
+
+    book
+}
+
+/// Run the preprocessors of the book on an edition, as `mdbook build` does.
+fn preprocess(ctx: &RenderContext, config: Config, renderer: &str, book: Book) -> Result<Book> {
+    let preprocessors = preprocessors(&config, renderer);
+
+    // The context has a private field, it is only built by deserializing
+    let preprocess_ctx: PreprocessorContext = serde_json::from_value(serde_json::json!({
+        "root": ctx.root,
+        "config": config,
+        "renderer": renderer,
+        "mdbook_version": ctx.version,
+    }))
+    .chain_err(|| "Unable to create the preprocessor context")?;
+
+    preprocessors.iter().try_fold(book, |book, preprocessor| {
+        debug!("Running the {} preprocessor.", preprocessor.name());
+        preprocessor.run(&preprocess_ctx, book)
+    })
+}
+
+/// The preprocessors of the book which run with `renderer`, with the rules of
+/// `mdbook build`.
+fn preprocessors(config: &Config, renderer: &str) -> Vec<Box<dyn Preprocessor>> {
+    let defaults = config.build.use_default_preprocessors;
+    let mut preprocessors: Vec<Box<dyn Preprocessor>> = vec![];
+
+    if defaults {
+        preprocessors.push(Box::new(LinkPreprocessor::new()));
+        preprocessors.push(Box::new(IndexPreprocessor::new()));
+    }
+
+    if let Some(table) = config.get("preprocessor").and_then(Value::as_table) {
+        for (name, table) in table {
+            match name.as_str() {
+                "links" if !defaults => preprocessors.push(Box::new(LinkPreprocessor::new())),
+                "index" if !defaults => preprocessors.push(Box::new(IndexPreprocessor::new())),
+                "links" | "index" => {}
+                _ => {
+                    let command = table
+                        .get("command")
+                        .and_then(Value::as_str)
+                        .map_or_else(|| format!("mdbook-{}", name), ToString::to_string);
+                    preprocessors.push(Box::new(CmdPreprocessor::new(name.clone(), command)));
+                }
+            }
+        }
+    }
+
+    preprocessors.retain(|preprocessor| {
+        let name = preprocessor.name();
+        let key = format!("preprocessor.{}.renderers", name);
+
+        match config.get(&key) {
+            Some(Value::Array(ref renderers))
+                if !(defaults && matches!(name, "links" | "index")) =>
+            {
+                renderers
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|name| name == renderer)
+            }
+            _ => preprocessor.supports_renderer(renderer),
+        }
+    });
+
+    preprocessors
+}
+
+/// Names of the chapters of a `SUMMARY.md`, by path.
+fn summary_names(summary: &book::Summary) -> HashMap<PathBuf, String> {
+    fn walk(items: &[SummaryItem], names: &mut HashMap<PathBuf, String>) {
+        for item in items {
+            if let SummaryItem::Link(ref link) = *item {
+                names.insert(link.location.clone(), link.name.clone());
+                walk(&link.nested_items, names);
+            }
+        }
+    }
+
+    let mut names = HashMap::new();
+    walk(&summary.prefix_chapters, &mut names);
+    walk(&summary.numbered_chapters, &mut names);
+    walk(&summary.suffix_chapters, &mut names);
+    names
+}
@@ -3,23 +3,52 @@
 //! An HTML renderer is a basic, extensible Markdown to HTML renderer
 //! engine for `mdbook`.
 
+use crate::api::anchors::{AnchorManifest, Anchors, ANCHOR_MANIFEST};
+use crate::api::cache::BuildCache;
+use crate::api::engine::ApiConfig;
+use crate::api::locale::Locale;
+use crate::api::optimize::OptimizeSink;
+use crate::api::redirect::{redirect_page, redirect_pages};
+use crate::api::sitemap::{is_host_root, robots, sitemap, sitemap_index};
 use crate::engine::Engine;
+use crate::sink::{FsSink, Sink};
 use crate::template::Template;
 use crate::theme::Theme;
 
+use rayon::prelude::*;
+use std::collections::HashSet;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use mdbook::book::{Book, BookItem, Chapter};
 use mdbook::errors::Result;
 use mdbook::errors::ResultExt;
 use mdbook::renderer::{RenderContext, Renderer};
 use mdbook::utils;
 
+pub mod anchors;
+pub mod cache;
+pub mod data;
 pub mod engine;
+pub mod front_matter;
+pub mod git;
+pub mod i18n;
+pub mod inline;
+pub mod locale;
+pub mod optimize;
 pub mod parser;
+pub mod prism;
+pub mod redirect;
+pub mod sitemap;
 pub mod template;
 pub mod theme;
+pub mod util;
 
-use engine::HtmlContext;
+pub use engine::{BookState, HtmlContext};
+
+/// Source of the page served for missing paths, next to `SUMMARY.md`.
+const NOT_FOUND: &str = "404.md";
 
 /// Must be extensible and configurable but shoud implement the logic
 /// for rendering HTML project, combining theme and template management.
@@ -28,21 +57,87 @@ pub struct HtmlRenderer<E: Engine<HtmlContext>, T: Template<HtmlContext, E::Outp
     engine: E,
     template: T,
     theme: T::Theme,
+    api_config: ApiConfig,
+    book_state: Arc<BookState>,
+    // Pages of the output with their heading ids
+    manifest: AnchorManifest,
 }
 
 impl<E: Engine<HtmlContext>, T: Template<HtmlContext, E::Output>> HtmlRenderer<E, T> {
     pub fn new(ctx: &RenderContext) -> Result<Self> {
+        let template = T::load_from_context(ctx)?;
+        let api_config = ApiConfig::from_context(ctx);
+        let book_state = BookState::new(ctx, &api_config, template.is_single_page())?;
+        Self::with_state(ctx, template, book_state)
+    }
+
+    /// Renderer of a book whose shared data is already known, e.g. an
+    /// edition of `output.api.locale`
+    fn with_state(ctx: &RenderContext, template: T, book_state: BookState) -> Result<Self> {
+        let api_config = ApiConfig::from_context(ctx);
+        let manifest =
+            AnchorManifest::new(&ctx.book, &book_state.anchors, template.is_single_page());
+
         Ok(HtmlRenderer {
-            engine: E::load_from_context(&ctx)?,
-            template: T::load_from_context(&ctx)?,
-            theme: T::Theme::load_from_context(&ctx)?,
+            engine: E::load_from_context(ctx)?,
+            template,
+            theme: T::Theme::load_from_context(ctx)?,
+            api_config,
+            book_state: Arc::new(book_state),
+            manifest,
         })
     }
 
+    /// Context of a page, sharing the data of the book
+    fn html_context(&self) -> HtmlContext {
+        HtmlContext {
+            book_state: self.book_state.clone(),
+            ..Default::default()
+        }
+    }
+
     pub fn name(&self) -> &str {
         self.engine.name()
     }
 
+    /// Render `404.md` from the source directory, or a default page, into
+    /// `404.html`.
+    fn render_not_found<S: Sink>(&self, ctx: &RenderContext, sink: &mut S) -> Result<()> {
+        let path = Path::new(NOT_FOUND);
+
+        if has_not_found_chapter(&ctx.book) {
+            warn!(
+                "{} is a chapter, no page is rendered for missing paths",
+                NOT_FOUND
+            );
+            return Ok(());
+        }
+
+        let strings = i18n::load(ctx)?;
+        let title = &strings["not_found_title"];
+
+        let source = ctx.source_dir().join(path);
+        let content = if source.exists() {
+            fs::read_to_string(&source)
+                .chain_err(|| format!("Unable to read {}", source.display()))?
+        } else {
+            let home = self.api_config.site_root();
+            let text = strings["not_found_text"].replace("{home}", &home);
+            format!("# {}\n\n{}\n", title, text)
+        };
+
+        let chapter = Chapter::new(title, content, path, vec![]);
+        let mut html_ctx = HtmlContext {
+            book_item: Some(BookItem::Chapter(chapter)),
+            is_not_found: true,
+            ..self.html_context()
+        };
+
+        let mut data = self.engine.process_chapter(ctx, &mut html_ctx)?;
+        self.template
+            .render_page(ctx, &self.theme, &mut html_ctx, &mut data, sink)
+    }
+
     fn clean_dest(&self, ctx: &RenderContext) -> Result<()> {
         if ctx.destination.exists() {
             utils::fs::remove_dir_content(&ctx.destination)
@@ -51,49 +146,389 @@ impl<E: Engine<HtmlContext>, T: Template<HtmlContext, E::Output>> HtmlRenderer<E
         Ok(())
     }
 
-    pub fn render(&self, ctx: &RenderContext) -> Result<()> {
-        let book = &ctx.book;
+    /// Render the book into its destination folder, or each edition into its
+    /// own folder with `output.api.locale`
+    pub fn render(&self, ctx: &RenderContext) -> Result<()>
+    where
+        E: Sync,
+        E::Output: Send,
+        T: Sync,
+        T::Theme: Sync,
+    {
+        let locales = &self.api_config.locale;
+        let default = match locales.first() {
+            Some(default) => default,
+            None => return self.render_edition(ctx),
+        };
+
+        locale::check(ctx, locales)?;
+        self.clean_root(ctx, locales)?;
+
+        // The editions link to the heading ids of each other
+        let editions = locale::editions(ctx, self.name())?;
+        let single_page = self.template.is_single_page();
+        let strategy = self.api_config.anchor_strategy(single_page);
+        let anchors: Vec<_> = editions
+            .iter()
+            .enumerate()
+            .map(|(index, edition)| {
+                let anchors = match index {
+                    0 => self.book_state.anchors.clone(),
+                    _ => Anchors::from_book(&edition.book, strategy, single_page),
+                };
+                (edition.locale.clone(), anchors)
+            })
+            .collect();
+        let anchors = Arc::new(anchors);
+
+        for (index, edition) in editions.into_iter().enumerate() {
+            let untranslated = edition.untranslated.clone();
+            let ctx = locale::context(ctx, edition)?;
+
+            let edition_anchors = anchors[index].1.clone();
+            let mut book_state =
+                BookState::with_anchors(&ctx, &self.api_config, edition_anchors, single_page)?;
+            book_state.editions = anchors.clone();
+            book_state.untranslated = untranslated;
+
+            let template = T::load_from_context(&ctx)?;
+            Self::with_state(&ctx, template, book_state)?.render_edition(&ctx)?;
+        }
+
+        self.render_root(ctx, default, FsSink::new(&ctx.destination))
+    }
+
+    /// Remove the files of an earlier build at the root of the editions, each
+    /// edition cleans its own folder and keeps its incremental build cache
+    fn clean_root(&self, ctx: &RenderContext, locales: &[Locale]) -> Result<()> {
+        let entries = match fs::read_dir(&ctx.destination) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(()),
+        };
+
+        let kept: Vec<_> = locales
+            .iter()
+            .map(|locale| ctx.destination.join(&locale.id))
+            .flat_map(|edition| vec![BuildCache::path(&edition), edition])
+            .collect();
+
+        for entry in entries {
+            let path = entry?.path();
+            let removed = if kept.contains(&path) {
+                Ok(())
+            } else if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+            removed.chain_err(|| "Unable to remove stale HTML output")?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the files static hosts and crawlers look for at the root of the
+    /// editions
+    fn render_root<S: Sink>(
+        &self,
+        ctx: &RenderContext,
+        default: &Locale,
+        sink: S,
+    ) -> Result<S::Output> {
+        let api_config = &self.api_config;
+        let mut sink = OptimizeSink::new(sink, api_config.optimize.clone());
+
+        // The root of the book leads to the default edition
+        let index = redirect_page(&format!("{}/index.html", default.id), false)?;
+        sink.write(Path::new("index.html"), index.as_bytes())?;
+
+        // The page of the default edition links to it from the site root,
+        // there is none when `404.md` is one of its chapters
+        if !has_not_found_chapter(&ctx.book) {
+            let not_found = ctx.destination.join(&default.id).join("404.html");
+            let content = fs::read(&not_found)
+                .chain_err(|| format!("Unable to read {}", not_found.display()))?;
+            sink.write(Path::new("404.html"), &content)?;
+        }
+
+        if api_config.site_url.is_some() {
+            let site_url = api_config.site_root();
+            let index = sitemap_index(&site_url, &api_config.locale);
+            sink.write(Path::new("sitemap.xml"), index.as_bytes())?;
+
+            self.write_robots(&site_url, &mut sink)?;
+        }
+
+        sink.finish()
+    }
+
+    /// Render one edition of the book into its destination folder
+    fn render_edition(&self, ctx: &RenderContext) -> Result<()>
+    where
+        E: Sync,
+        E::Output: Send,
+        T: Sync,
+        T::Theme: Sync,
+    {
         let destination = &ctx.destination;
+        let api_config = &self.api_config;
 
-        self.clean_dest(&ctx)?;
+        // Compare the anchors with the previous build before removing it
+        let previous = AnchorManifest::load(&destination.join(ANCHOR_MANIFEST));
+        let redirects = anchors::normalize_redirects(&api_config.redirects);
+        self.manifest
+            .check(previous.as_ref(), &redirects, api_config.removed_anchors)?;
 
-        fs::create_dir_all(&destination)
-            .chain_err(|| "Unexpected error when constructing destination path")?;
+        if !api_config.incremental {
+            self.clean_dest(ctx)?;
 
-        self.template.initialize_book(&ctx, &self.theme)?;
+            fs::create_dir_all(destination)
+                .chain_err(|| "Unexpected error when constructing destination path")?;
 
-        let mut html_ctx = HtmlContext::default();
-        html_ctx.is_index = true;
+            return self.render_to(ctx, FsSink::new(destination));
+        }
+
+        let cache_path = BuildCache::path(destination);
+        let renderer = std::any::type_name::<Self>();
+        let cache = BuildCache::from_context(ctx, renderer, &self.theme, &self.book_state)?;
 
-        for item in book.iter() {
-            html_ctx.book_item = Some(item.clone());
+        let changed = match BuildCache::load(&cache_path) {
+            Some(ref previous) if destination.exists() => cache.changed_chapters(previous),
+            _ => None,
+        };
 
-            let mut data = self.engine.process_chapter(&ctx, &mut html_ctx)?;
+        // Remove the cache first so that a failed build is never reused
+        if cache_path.exists() {
+            fs::remove_file(&cache_path)?;
+        }
 
-            self.template
-                .render_chapter(&ctx, &self.theme, &mut html_ctx, &mut data)?;
+        match changed {
+            Some(ref changed) => {
+                info!("Incremental build, {} chapter(s) changed", changed.len());
+            }
+            None => {
+                self.clean_dest(ctx)?;
 
-            html_ctx.is_index = false;
+                fs::create_dir_all(destination)
+                    .chain_err(|| "Unexpected error when constructing destination path")?;
+            }
         }
 
-        let mut data = self.engine.finalize_book(&ctx, &mut html_ctx)?;
-        self.template.finalize_book(&ctx, &self.theme, &mut data)?;
+        self.render_chapters(ctx, FsSink::new(destination), changed.as_ref())?;
+
+        cache.save(&cache_path)
+    }
+
+    /// Render the book into the given sink and return its output, e.g. with
+    /// a `MemorySink` every generated path with its content.
+    ///
+    /// Chapters are processed and rendered on a thread pool, their files are
+    /// then written to the sink in `SUMMARY.md` order.
+    pub fn render_to<S: Sink>(&self, ctx: &RenderContext, sink: S) -> Result<S::Output>
+    where
+        E: Sync,
+        E::Output: Send,
+        T: Sync,
+        T::Theme: Sync,
+    {
+        self.render_chapters(ctx, sink, None)
+    }
+
+    /// Render the book, or when `changed` is given only the chapters whose
+    /// path it contains. Static files are then considered up to date.
+    fn render_chapters<S: Sink>(
+        &self,
+        ctx: &RenderContext,
+        sink: S,
+        changed: Option<&HashSet<String>>,
+    ) -> Result<S::Output>
+    where
+        E: Sync,
+        E::Output: Send,
+        T: Sync,
+        T::Theme: Sync,
+    {
+        let book = &ctx.book;
+        let api_config = &self.api_config;
+        let mut sink = OptimizeSink::new(sink, api_config.optimize.clone());
 
-        self.theme.copy_static_files(&ctx)
+        if changed.map(HashSet::is_empty) == Some(true) {
+            self.write_sitemap(ctx, &mut sink)?;
+            return sink.finish();
+        }
+
+        let rebuild = changed.is_none();
+
+        // Check the redirects before writing anything
+        let redirect_pages = if rebuild {
+            redirect_pages(&api_config.redirect, &self.manifest)?
+        } else {
+            vec![]
+        };
+        // A single page must be rendered again as a whole
+        let changed = changed.filter(|_| !self.template.is_single_page());
+
+        let mut html_ctx = self.html_context();
+        let mut data = self.engine.initialize_book(ctx, &mut html_ctx)?;
+        self.template
+            .initialize_book(ctx, &self.theme, &mut data, &mut sink)?;
+
+        let items: Vec<_> = book.iter().enumerate().collect();
+
+        // Render the chapters by batches so that only the output of a batch is
+        // kept in memory before being written in order
+        for batch in items.chunks(rayon::current_num_threads() * 4) {
+            let chapters = batch
+                .par_iter()
+                .map(|&(index, item)| {
+                    let mut html_ctx = HtmlContext {
+                        book_item: Some(item.clone()),
+                        is_index: index == 0,
+                        ..self.html_context()
+                    };
+                    let mut chapter_sink = BufferSink::default();
+
+                    if let (Some(changed), BookItem::Chapter(ch)) = (changed, item) {
+                        if !changed.contains(&*ch.path.to_string_lossy()) {
+                            return Ok(chapter_sink);
+                        }
+                    }
+
+                    let mut data = self.engine.process_chapter(ctx, &mut html_ctx)?;
+
+                    self.template.render_chapter(
+                        ctx,
+                        &self.theme,
+                        &mut html_ctx,
+                        &mut data,
+                        &mut chapter_sink,
+                    )?;
+
+                    Ok(chapter_sink)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            for chapter_sink in chapters {
+                chapter_sink.replay(&mut sink)?;
+            }
+        }
+
+        let mut html_ctx = self.html_context();
+        let mut data = self.engine.finalize_book(ctx, &mut html_ctx)?;
+        self.template
+            .finalize_book(ctx, &self.theme, &mut data, &mut sink)?;
+
+        if rebuild {
+            self.theme.copy_static_files(ctx, &mut sink)?;
+
+            sink.write(Path::new(ANCHOR_MANIFEST), &self.manifest.to_vec()?)?;
+
+            for (path, content) in redirect_pages {
+                sink.write(&path, content.as_bytes())?;
+            }
+
+            self.render_not_found(ctx, &mut sink)?;
+
+            // The root of the editions has the robots.txt
+            if self.book_state.editions.is_empty() && api_config.site_url.is_some() {
+                self.write_robots(&api_config.site_root(), &mut sink)?;
+            }
+        }
+
+        self.write_sitemap(ctx, &mut sink)?;
+
+        sink.finish()
+    }
+
+    /// Write the sitemap of the book when its URL is known. Modification
+    /// dates change without a rebuild, so it is written by every build.
+    fn write_sitemap<S: Sink>(&self, ctx: &RenderContext, sink: &mut S) -> Result<()> {
+        if self.api_config.site_url.is_none() {
+            return Ok(());
+        }
+
+        let site_url = self.api_config.site_root();
+        let sitemap = sitemap(&ctx.book, &ctx.source_dir(), &self.book_state, &site_url);
+        sink.write(Path::new("sitemap.xml"), sitemap.as_bytes())
+    }
+
+    /// Write the robots.txt pointing to the sitemap, crawlers only read it at
+    /// the root of the host
+    fn write_robots<S: Sink>(&self, site_url: &str, sink: &mut S) -> Result<()> {
+        if is_host_root(site_url) {
+            sink.write(Path::new("robots.txt"), robots(site_url).as_bytes())
+        } else {
+            warn!(
+                "robots.txt is not written, crawlers only read it at the root of the host, not {}",
+                site_url
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Whether `404.md` is a chapter of the book, which then has no page for the
+/// missing paths.
+fn has_not_found_chapter(book: &Book) -> bool {
+    book.iter().any(|item| match *item {
+        BookItem::Chapter(ref ch) => ch.path == Path::new(NOT_FOUND),
+        BookItem::Separator => false,
+    })
+}
+
+/// Keep the files written while rendering a chapter, to write them later in
+/// the same order.
+#[derive(Default)]
+struct BufferSink {
+    files: Vec<(PathBuf, Vec<u8>, bool)>,
+}
+
+impl BufferSink {
+    fn replay<S: Sink>(self, sink: &mut S) -> Result<()> {
+        for (path, content, append) in self.files {
+            if append {
+                sink.append(&path, &content)?;
+            } else {
+                sink.write(&path, &content)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Sink for BufferSink {
+    type Output = ();
+
+    fn write(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        self.files
+            .push((path.to_path_buf(), content.to_owned(), false));
+        Ok(())
+    }
+
+    fn append(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        self.files
+            .push((path.to_path_buf(), content.to_owned(), true));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        Ok(())
     }
 }
 
 /// Implement mdbook `Renderer` for all HtmlRenderer
 impl<E, T> Renderer for HtmlRenderer<E, T>
 where
-    E: Engine<HtmlContext>,
-    T: Template<HtmlContext, E::Output>,
+    E: Engine<HtmlContext> + Sync,
+    E::Output: Send,
+    T: Template<HtmlContext, E::Output> + Sync,
+    T::Theme: Sync,
 {
     fn name(&self) -> &str {
         self.name()
     }
 
     fn render(&self, ctx: &RenderContext) -> Result<()> {
-        self.render(&ctx)
+        self.render(ctx)
     }
 }
@@ -0,0 +1,610 @@
+//! Minify the generated files and write precompressed copies, see
+//! `output.api.optimize`.
+//!
+//! The minifiers are conservative: they remove comments and collapse
+//! whitespace but never rewrite the code itself.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::sink::Sink;
+
+use mdbook::errors::Result;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct OptimizeConfig {
+    /// Minify HTML pages, stylesheets and scripts
+    pub minify: bool,
+    /// Write a `.gz` copy of every text file
+    pub gzip: bool,
+    /// Write a `.br` copy of every text file
+    pub brotli: bool,
+}
+
+/// Wrap a sink to optimize the files written through it.
+pub struct OptimizeSink<S: Sink> {
+    inner: S,
+    config: OptimizeConfig,
+    /// Compressed copy of the last text file written, kept open until the
+    /// next write as only this file can still be appended to
+    compressed: Option<(PathBuf, Encoders)>,
+}
+
+struct Encoders {
+    gzip: Option<GzEncoder<Vec<u8>>>,
+    brotli: Option<brotli::CompressorWriter<Vec<u8>>>,
+}
+
+impl Encoders {
+    fn write_all(&mut self, content: &[u8]) -> Result<()> {
+        if let Some(ref mut encoder) = self.gzip {
+            encoder.write_all(content)?;
+        }
+        if let Some(ref mut encoder) = self.brotli {
+            encoder.write_all(content)?;
+        }
+        Ok(())
+    }
+
+    /// The compressed copies with the suffix of their file name.
+    fn finish(self) -> Result<Vec<(&'static str, Vec<u8>)>> {
+        let mut copies = vec![];
+        if let Some(encoder) = self.gzip {
+            copies.push(("gz", encoder.finish()?));
+        }
+        if let Some(encoder) = self.brotli {
+            copies.push(("br", encoder.into_inner()));
+        }
+        Ok(copies)
+    }
+}
+
+impl<S: Sink> OptimizeSink<S> {
+    pub fn new(inner: S, config: OptimizeConfig) -> Self {
+        OptimizeSink {
+            inner,
+            config,
+            compressed: None,
+        }
+    }
+
+    fn minify(&self, path: &Path, content: &[u8]) -> Option<String> {
+        let text = std::str::from_utf8(content)
+            .ok()
+            .filter(|_| self.config.minify)?;
+
+        match extension(path) {
+            "html" => Some(minify_html(text)),
+            "css" => Some(minify_css(text)),
+            "js" => Some(minify_js(text)),
+            _ => None,
+        }
+    }
+
+    /// Write the compressed copies of the last text file.
+    fn write_compressed(&mut self) -> Result<()> {
+        if let Some((path, encoders)) = self.compressed.take() {
+            for (suffix, content) in encoders.finish()? {
+                self.inner.write(&with_suffix(&path, suffix), &content)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: Sink> Sink for OptimizeSink<S> {
+    type Output = S::Output;
+
+    fn write(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        let minified = self.minify(path, content);
+        let content = minified.as_ref().map_or(content, String::as_bytes);
+
+        self.write_compressed()?;
+        self.inner.write(path, content)?;
+
+        let is_text = matches!(
+            extension(path),
+            "html" | "css" | "js" | "json" | "svg" | "xml" | "txt"
+        );
+
+        if is_text && (self.config.gzip || self.config.brotli) {
+            let mut encoders = Encoders {
+                gzip: Some(GzEncoder::new(Vec::new(), Compression::best()))
+                    .filter(|_| self.config.gzip),
+                brotli: Some(brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22))
+                    .filter(|_| self.config.brotli),
+            };
+            encoders.write_all(content)?;
+            self.compressed = Some((path.to_path_buf(), encoders));
+        }
+
+        Ok(())
+    }
+
+    fn append(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        let minified = self.minify(path, content);
+        let content = minified.as_ref().map_or(content, String::as_bytes);
+
+        self.inner.append(path, content)?;
+
+        match self.compressed {
+            Some((ref last, ref mut encoders)) if last == path => encoders.write_all(content)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Self::Output> {
+        self.write_compressed()?;
+        self.inner.finish()
+    }
+}
+
+fn extension(path: &Path) -> &str {
+    path.extension().and_then(|ext| ext.to_str()).unwrap_or("")
+}
+
+/// Append a suffix to the file name, e.g. `index.html.gz`.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Remove comments and collapse whitespace outside of `pre` and `textarea`
+/// elements. Inline styles and scripts are minified too.
+pub fn minify_html(html: &str) -> String {
+    lazy_static! {
+        static ref RAW_TEXT: Regex = Regex::new(r"(?i)^<(pre|textarea|script|style)[\s>]").unwrap();
+        static ref CLOSE_PRE: Regex = Regex::new(r"(?i)</pre").unwrap();
+        static ref CLOSE_TEXTAREA: Regex = Regex::new(r"(?i)</textarea").unwrap();
+        static ref CLOSE_SCRIPT: Regex = Regex::new(r"(?i)</script").unwrap();
+        static ref CLOSE_STYLE: Regex = Regex::new(r"(?i)</style").unwrap();
+        static ref JS_TYPE: Regex =
+            Regex::new(r#"(?i)\stype=["']?(?:text/javascript|module)"#).unwrap();
+        static ref ANY_TYPE: Regex = Regex::new(r"(?i)\stype=").unwrap();
+    }
+
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html.trim_start();
+
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |end| end + 3);
+            // Keep conditional comments
+            if rest.starts_with("<!--[if") {
+                out.push_str(&rest[..end]);
+            }
+            rest = &rest[end..];
+        } else if rest.starts_with('<') {
+            let end = tag_end(rest);
+            let tag = &rest[..end];
+            out.push_str(tag);
+            rest = &rest[end..];
+
+            if let Some(caps) = RAW_TEXT.captures(tag) {
+                let name = caps[1].to_ascii_lowercase();
+                let close: &Regex = match name.as_str() {
+                    "pre" => &CLOSE_PRE,
+                    "textarea" => &CLOSE_TEXTAREA,
+                    "script" => &CLOSE_SCRIPT,
+                    _ => &CLOSE_STYLE,
+                };
+                let end = close.find(rest).map_or(rest.len(), |m| m.start());
+                let text = &rest[..end];
+
+                match name.as_str() {
+                    "script" if JS_TYPE.is_match(tag) || !ANY_TYPE.is_match(tag) => {
+                        out.push_str(&minify_js(text))
+                    }
+                    "style" => out.push_str(&minify_css(text)),
+                    _ => out.push_str(text),
+                }
+                rest = &rest[end..];
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            collapse_whitespace(&rest[..end], &mut out);
+            rest = &rest[end..];
+        }
+    }
+
+    out
+}
+
+/// Length of the tag at the start of `html`, ignoring `>` in quoted attributes.
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+
+    for (i, c) in html.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+
+    html.len()
+}
+
+fn collapse_whitespace(text: &str, out: &mut String) {
+    let mut in_whitespace = false;
+
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            // A removed comment may leave two runs of whitespace side by side
+            if !in_whitespace && !out.ends_with(' ') {
+                out.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            out.push(c);
+            in_whitespace = false;
+        }
+    }
+}
+
+/// Remove comments, except `/*! ... */` ones, and unneeded whitespace.
+pub fn minify_css(css: &str) -> String {
+    let mut out = String::with_capacity(css.len());
+    let mut chars = css.chars().peekable();
+    let mut pending_space = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let keep = chars.peek() == Some(&'!');
+                let mut comment = String::from("/*");
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    comment.push(c);
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                if keep {
+                    out.push_str(&comment);
+                    out.push('\n');
+                }
+            }
+            '"' | '\'' => {
+                push_pending_space(&mut out, &mut pending_space, c);
+                out.push(c);
+                copy_string(c, &mut chars, &mut out);
+            }
+            c if c.is_whitespace() => pending_space = true,
+            c => {
+                if c == '}' && out.ends_with(';') {
+                    out.pop();
+                }
+                push_pending_space(&mut out, &mut pending_space, c);
+                out.push(c);
+            }
+        }
+    }
+
+    out
+}
+
+fn push_pending_space(out: &mut String, pending_space: &mut bool, next: char) {
+    if *pending_space {
+        let skip =
+            out.is_empty() || out.ends_with(|c| "{};,>:\n".contains(c)) || "{};,>".contains(next);
+        if !skip {
+            out.push(' ');
+        }
+        *pending_space = false;
+    }
+}
+
+/// Copy the rest of a string literal delimited by `quote`, escapes included.
+fn copy_string<I>(quote: char, chars: &mut std::iter::Peekable<I>, out: &mut String)
+where
+    I: Iterator<Item = char>,
+{
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            }
+            '\n' => break,
+            c if c == quote => break,
+            _ => {}
+        }
+    }
+}
+
+/// Remove comments, except `/*! ... */` ones, indentation and blank lines.
+/// Line breaks are kept so that automatic semicolon insertion still applies.
+pub fn minify_js(js: &str) -> String {
+    let mut out = String::with_capacity(js.len());
+    let mut chars = js.chars().peekable();
+    // Last significant character, to tell regex literals from divisions
+    let mut last = '\n';
+    let mut last_word = String::new();
+    // Nesting of template literals, counting the braces of each `${`
+    let mut templates: Vec<usize> = Vec::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        push_newline(&mut out);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let keep = chars.peek() == Some(&'!');
+                let mut comment = String::from("/*");
+                let mut previous = ' ';
+                let mut has_newline = false;
+                for c in chars.by_ref() {
+                    comment.push(c);
+                    has_newline |= c == '\n';
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                if keep {
+                    out.push_str(&comment);
+                    push_newline(&mut out);
+                } else if has_newline {
+                    push_newline(&mut out);
+                } else {
+                    push_space(&mut out);
+                }
+            }
+            '/' if starts_regex(last, &last_word) => {
+                out.push(c);
+                let mut in_class = false;
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                out.push(escaped);
+                            }
+                        }
+                        '[' => in_class = true,
+                        ']' => in_class = false,
+                        '/' if !in_class => break,
+                        '\n' => break,
+                        _ => {}
+                    }
+                }
+                last = '/';
+                last_word.clear();
+            }
+            '"' | '\'' => {
+                out.push(c);
+                copy_string(c, &mut chars, &mut out);
+                last = c;
+                last_word.clear();
+            }
+            '`' => {
+                out.push(c);
+                copy_template(&mut chars, &mut out, &mut templates);
+                last = c;
+                last_word.clear();
+            }
+            '{' if !templates.is_empty() => {
+                *templates.last_mut().unwrap() += 1;
+                out.push(c);
+                last = c;
+                last_word.clear();
+            }
+            '}' if !templates.is_empty() => {
+                out.push(c);
+                let depth = templates.last_mut().unwrap();
+                if *depth == 0 {
+                    // End of the `${...}` substitution, back in the template
+                    templates.pop();
+                    copy_template(&mut chars, &mut out, &mut templates);
+                    last = '`';
+                } else {
+                    *depth -= 1;
+                    last = c;
+                }
+                last_word.clear();
+            }
+            '\n' => push_newline(&mut out),
+            c if c.is_whitespace() => push_space(&mut out),
+            c => {
+                out.push(c);
+                if c.is_alphanumeric() || c == '_' || c == '$' {
+                    if !(last.is_alphanumeric() || last == '_' || last == '$') {
+                        last_word.clear();
+                    }
+                    last_word.push(c);
+                } else {
+                    last_word.clear();
+                }
+                last = c;
+            }
+        }
+    }
+
+    let trimmed = out.trim_end().len();
+    out.truncate(trimmed);
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Copy a template literal up to its closing backtick, or up to the start of a
+/// `${...}` substitution which is then pushed on `templates`.
+fn copy_template<I>(
+    chars: &mut std::iter::Peekable<I>,
+    out: &mut String,
+    templates: &mut Vec<usize>,
+) where
+    I: Iterator<Item = char>,
+{
+    while let Some(c) = chars.next() {
+        out.push(c);
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            }
+            '$' if chars.peek() == Some(&'{') => {
+                out.push(chars.next().unwrap());
+                templates.push(0);
+                return;
+            }
+            '`' => return,
+            _ => {}
+        }
+    }
+}
+
+/// Whether a `/` following `last` starts a regex literal rather than a division.
+fn starts_regex(last: char, last_word: &str) -> bool {
+    if !last_word.is_empty() {
+        return matches!(
+            last_word,
+            "return"
+                | "typeof"
+                | "instanceof"
+                | "in"
+                | "of"
+                | "new"
+                | "delete"
+                | "void"
+                | "throw"
+                | "case"
+                | "do"
+                | "else"
+                | "yield"
+                | "await"
+        );
+    }
+
+    !(last.is_alphanumeric() || matches!(last, '_' | '$' | ')' | ']' | '}' | '"' | '\'' | '`'))
+}
+
+fn push_space(out: &mut String) {
+    if !out.is_empty() && !out.ends_with(|c: char| c.is_whitespace()) {
+        out.push(' ');
+    }
+}
+
+fn push_newline(out: &mut String) {
+    let trimmed = out.trim_end_matches([' ', '\t']).len();
+    out.truncate(trimmed);
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::MemorySink;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn js_regex_literals_and_divisions() {
+        assert_eq!(minify_js("a = b / c / d;"), "a = b / c / d;\n");
+        assert_eq!(
+            minify_js("var re = /\\/\\/ [/]  x/g;"),
+            "var re = /\\/\\/ [/]  x/g;\n"
+        );
+        assert_eq!(minify_js("return /  a/.test(s)"), "return /  a/.test(s)\n");
+        assert_eq!(minify_js("(x) / 2 // half"), "(x) / 2\n");
+    }
+
+    #[test]
+    fn js_template_literals() {
+        let js = "let s = `a  ${ {b: 1}.b + `  ${c}  ` }  // d`;\n// e\n";
+        assert_eq!(
+            minify_js(js),
+            "let s = `a  ${ {b: 1}.b + `  ${c}  ` }  // d`;\n"
+        );
+    }
+
+    #[test]
+    fn js_and_css_keep_bang_comments() {
+        assert_eq!(
+            minify_js("/*! License */\n/* note */ a();"),
+            "/*! License */\na();\n"
+        );
+        assert_eq!(
+            minify_css("/*! License */ a { color: red; } /* note */"),
+            "/*! License */\na{color:red}"
+        );
+    }
+
+    #[test]
+    fn css_descendant_pseudo_class() {
+        assert_eq!(
+            minify_css(".nav :focus { outline: 0 }"),
+            ".nav :focus{outline:0}"
+        );
+        assert_eq!(minify_css("a:hover , b > i {}"), "a:hover,b>i{}");
+    }
+
+    #[test]
+    fn html_keeps_raw_text() {
+        let html = concat!(
+            "<p>a   <!-- c -->  b</p>\n",
+            "<pre>  x\n  y</pre>\n",
+            "<textarea>  z  </textarea>\n",
+            "<script type=\"application/ld+json\">{ \"a\":  \"//b\" }</script>\n",
+            "<script>\n  f(); // call\n</script>",
+        );
+        assert_eq!(
+            minify_html(html),
+            concat!(
+                "<p>a b</p> ",
+                "<pre>  x\n  y</pre> ",
+                "<textarea>  z  </textarea> ",
+                "<script type=\"application/ld+json\">{ \"a\":  \"//b\" }</script> ",
+                "<script>f();\n</script>",
+            )
+        );
+    }
+
+    #[test]
+    fn compress_appended_files() {
+        let config = OptimizeConfig {
+            gzip: true,
+            ..OptimizeConfig::default()
+        };
+        let mut sink = OptimizeSink::new(MemorySink::new(), config);
+        sink.write(Path::new("a.txt"), b"one ").unwrap();
+        sink.append(Path::new("a.txt"), b"two").unwrap();
+        sink.write(Path::new("b.txt"), b"three").unwrap();
+        let files = sink.finish().unwrap();
+
+        let gunzip = |path: &str| {
+            let mut text = String::new();
+            GzDecoder::new(&files[Path::new(path)][..])
+                .read_to_string(&mut text)
+                .unwrap();
+            text
+        };
+        assert_eq!(gunzip("a.txt.gz"), "one two");
+        assert_eq!(gunzip("b.txt.gz"), "three");
+    }
+}
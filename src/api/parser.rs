@@ -1,3 +1,5 @@
+use crate::api::util::has_scheme;
+
 use pulldown_cmark::{CowStr, Event, Tag};
 use regex::Regex;
 use std::fmt::Write;
@@ -33,7 +35,6 @@ pub fn clean_codeblock_headers(event: Event<'_>) -> Event<'_> {
 /// but that is very difficult.
 pub fn adjust_links<'a>(event: Event<'a>, path: Option<&Path>) -> Event<'a> {
     lazy_static! {
-        static ref SCHEME_LINK: Regex = Regex::new(r"^[a-z][a-z0-9+.-]*:").unwrap();
         static ref MD_LINK: Regex = Regex::new(r"(?P<link>.*)\.md(?P<anchor>#.*)?").unwrap();
     }
 
@@ -51,7 +52,7 @@ pub fn adjust_links<'a>(event: Event<'a>, path: Option<&Path>) -> Event<'a> {
             }
         }
         // Don't modify links with schemes like `https`.
-        if !SCHEME_LINK.is_match(&dest) {
+        if !has_scheme(&dest) {
             // This is a relative link, adjust it as necessary.
             let mut fixed_link = String::new();
             if let Some(path) = path {
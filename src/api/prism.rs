@@ -0,0 +1,136 @@
+//! Bundle Prism with the grammars needed by a book.
+//!
+//! Prism core and its grammars are vendored in `theme/prism/`, its README gives
+//! the version. Only the grammars matching the configured `lang` ids and the
+//! languages of the code blocks are concatenated into the `prism.js` asset.
+
+use crate::api::front_matter;
+
+use pulldown_cmark::{Event, Parser, Tag};
+use std::collections::BTreeSet;
+
+use mdbook::book::{Book, BookItem};
+
+static CORE: &[u8] = include_bytes!("../../theme/prism/prism-core.js");
+
+/// A Prism grammar and the grammars it extends.
+struct Component {
+    id: &'static str,
+    aliases: &'static [&'static str],
+    requires: &'static [&'static str],
+    source: &'static [u8],
+}
+
+macro_rules! component {
+    ($id:expr, [$($alias:expr),*], [$($require:expr),*]) => {
+        Component {
+            id: $id,
+            aliases: &[$($alias),*],
+            requires: &[$($require),*],
+            source: include_bytes!(concat!("../../theme/prism/components/prism-", $id, ".js")),
+        }
+    };
+}
+
+/// Vendored grammars, a grammar is always listed after the ones it requires.
+static COMPONENTS: &[Component] = &[
+    component!("markup", ["html", "xml", "svg", "mathml"], []),
+    component!("css", [], []),
+    component!("clike", [], []),
+    component!("javascript", ["js"], ["clike"]),
+    component!("json", [], []),
+    component!("bash", ["shell", "sh"], []),
+    component!("python", ["py"], []),
+    component!("ruby", ["rb"], ["clike"]),
+    component!("go", [], ["clike"]),
+    component!("java", [], ["clike"]),
+    component!("php", [], ["clike"]),
+    component!("c", [], ["clike"]),
+    component!("cpp", [], ["c"]),
+    component!("csharp", ["cs", "dotnet"], ["clike"]),
+    component!("http", [], []),
+    component!("rust", [], []),
+    component!("yaml", ["yml"], []),
+    component!("sql", [], []),
+    component!("typescript", ["ts"], ["javascript"]),
+];
+
+/// Code block languages which are not meant to be highlighted.
+const PLAIN: &[&str] = &["", "text", "txt", "plain", "plaintext", "none", "ignore"];
+
+/// Concatenate Prism core with the grammars of `languages`, given as Prism
+/// ids or aliases. Unknown languages are reported and skipped.
+pub fn bundle<'a, I>(languages: I) -> Vec<u8>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut selected = BTreeSet::new();
+
+    for language in languages {
+        if PLAIN.contains(&language) {
+            continue;
+        }
+
+        match find(language) {
+            Some(component) => select(component, &mut selected),
+            None => warn!(
+                "No Prism grammar is bundled for `{}`, its code blocks won't be highlighted",
+                language
+            ),
+        }
+    }
+
+    let mut bundle = CORE.to_owned();
+    for component in COMPONENTS.iter().filter(|c| selected.contains(c.id)) {
+        bundle.push(b'\n');
+        bundle.extend_from_slice(component.source);
+    }
+    bundle
+}
+
+/// Collect the languages of the fenced code blocks of the book, i.e. the first
+/// word of their info string.
+pub fn book_languages(book: &Book) -> BTreeSet<String> {
+    let mut languages = BTreeSet::new();
+
+    for item in book.iter() {
+        if let BookItem::Chapter(ref ch) = *item {
+            // Language tabs added by the front matter, its errors are
+            // reported by the engine
+            if let Ok((_, front_matter, _)) = front_matter::parse(&ch.content) {
+                let tabs = front_matter.languages.into_iter();
+                languages.extend(tabs.map(|tab| tab.into_language().id));
+            }
+
+            for event in Parser::new(front_matter::strip(&ch.content)) {
+                if let Event::Start(Tag::CodeBlock(ref info)) = event {
+                    let language = info
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .next()
+                        .unwrap_or("");
+                    languages.insert(language.to_owned());
+                }
+            }
+        }
+    }
+
+    languages
+}
+
+fn find(language: &str) -> Option<&'static Component> {
+    COMPONENTS
+        .iter()
+        .find(|c| c.id == language || c.aliases.contains(&language))
+}
+
+fn select(component: &'static Component, selected: &mut BTreeSet<&'static str>) {
+    if !selected.insert(component.id) {
+        return;
+    }
+
+    for require in component.requires {
+        if let Some(required) = find(require) {
+            select(required, selected);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Pages redirecting the old paths of moved chapters, see
+//! `output.api.redirect`.
+//!
+//! ```toml
+//! [output.api.redirect]
+//! "users.html" = "users/index.html"
+//! "errors/codes.html" = "errors.html#codes"
+//! ```
+
+use crate::api::anchors::AnchorManifest;
+use crate::api::util::escape;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use mdbook::errors::Result;
+use mdbook::utils;
+
+/// Build the redirect page of every old path, checking that the new path and
+/// its anchor exist in the output.
+pub fn redirect_pages(
+    redirects: &BTreeMap<String, String>,
+    manifest: &AnchorManifest,
+) -> Result<Vec<(PathBuf, String)>> {
+    let mut pages = vec![];
+
+    for (old, new) in redirects {
+        let old = old.trim_start_matches('/');
+        let new = new.trim_start_matches('/');
+
+        if manifest.contains(old) {
+            return Err(format!("The redirect {} would replace a page of the book", old).into());
+        }
+
+        if !manifest.contains(new) {
+            return Err(format!("The redirect of {} targets an unknown page {}", old, new).into());
+        }
+
+        let path = PathBuf::from(old);
+        let url = utils::fs::path_to_root(&path) + new;
+        pages.push((path, redirect_page(&url, new.contains('#'))?));
+    }
+
+    Ok(pages)
+}
+
+/// Page sending the reader to `url`, keeping the anchor unless it has one.
+pub fn redirect_page(url: &str, has_anchor: bool) -> Result<String> {
+    let script_url = serde_json::to_string(url)?.replace("</", "<\\/");
+    // Keep the anchor of the old link when the new one has none
+    let script = if has_anchor {
+        format!("location.replace({})", script_url)
+    } else {
+        format!("location.replace({} + location.hash)", script_url)
+    };
+
+    Ok(format!(
+        r#"<!doctype html>
+<html>
+    <head>
+        <meta charset="utf-8">
+        <title>Redirecting...</title>
+        <link rel="canonical" href="{url}">
+        <meta http-equiv="refresh" content="0; url={url}">
+        <script>{script}</script>
+    </head>
+    <body>
+        <p>This page has moved to <a href="{url}">{url}</a>.</p>
+    </body>
+</html>
+"#,
+        url = escape(url),
+        script = script,
+    ))
+}
@@ -0,0 +1,151 @@
+//! `sitemap.xml` and `robots.txt` of the book, written when
+//! `output.api.site_url` is set.
+//!
+//! The sitemap lists every page with the date of its last commit with
+//! `output.api.git_metadata`, or the modification date of its source. The one
+//! page output is listed with the anchor of each chapter. With
+//! `output.api.locale`, the root of the output has a sitemap index of the
+//! sitemaps of the editions. Crawlers only read `robots.txt` at the root of a
+//! host, it is left out when the book is served from a sub-path.
+
+use crate::api::anchors::page_name;
+use crate::api::engine::BookState;
+use crate::api::locale::Locale;
+use crate::api::util::escape;
+
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use mdbook::book::{Book, BookItem};
+
+/// Public URL of a page, the index being the root of the book.
+pub fn page_url(site_url: &str, page: &str) -> String {
+    if page == "index.html" {
+        site_url.to_owned()
+    } else {
+        format!("{}{}", site_url, page)
+    }
+}
+
+pub fn sitemap(book: &Book, source_dir: &Path, book_state: &BookState, site_url: &str) -> String {
+    let single_page = book_state.single_page;
+    let mut urls = vec![];
+
+    for (index, item) in book.iter().enumerate() {
+        if let BookItem::Chapter(ref ch) = *item {
+            // Checkouts give every file the same modification date
+            let modified = match book_state.last_commits.get(&ch.path) {
+                Some(commit) => Some(commit.date.clone()),
+                None => fs::metadata(source_dir.join(&ch.path))
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(date),
+            };
+
+            let url = if single_page {
+                match book_state.anchors.first(&ch.path) {
+                    Some(anchor) => format!("{}#{}", site_url, anchor),
+                    None => continue,
+                }
+            } else if index == 0 {
+                site_url.to_owned()
+            } else {
+                page_url(site_url, &page_name(&ch.path))
+            };
+
+            urls.push((url, modified));
+        }
+    }
+
+    if single_page {
+        // The page itself changed with its most recent chapter
+        let modified = urls
+            .iter()
+            .filter_map(|(_, modified)| modified.clone())
+            .max();
+        urls.insert(0, (site_url.to_owned(), modified));
+    }
+
+    let mut sitemap = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+
+    for (url, modified) in urls {
+        sitemap.push_str("  <url>\n");
+        sitemap.push_str(&format!("    <loc>{}</loc>\n", escape(&url)));
+        if let Some(date) = modified {
+            sitemap.push_str(&format!("    <lastmod>{}</lastmod>\n", date));
+        }
+        sitemap.push_str("  </url>\n");
+    }
+
+    sitemap.push_str("</urlset>\n");
+    sitemap
+}
+
+/// Index of the sitemaps of the editions, in the folder of each locale.
+pub fn sitemap_index(site_url: &str, locales: &[Locale]) -> String {
+    let mut index = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+
+    for locale in locales {
+        let url = format!("{}{}/sitemap.xml", site_url, locale.id);
+        index.push_str("  <sitemap>\n");
+        index.push_str(&format!("    <loc>{}</loc>\n", escape(&url)));
+        index.push_str("  </sitemap>\n");
+    }
+
+    index.push_str("</sitemapindex>\n");
+    index
+}
+
+/// Whether `site_url` is the root of its host, where `robots.txt` is read.
+pub fn is_host_root(site_url: &str) -> bool {
+    let host = site_url.find("//").map_or(0, |index| index + 2);
+    match site_url[host..].find('/') {
+        Some(path) => &site_url[host + path..] == "/",
+        None => true,
+    }
+}
+
+pub fn robots(site_url: &str) -> String {
+    format!(
+        "User-agent: *\nAllow: /\n\nSitemap: {}sitemap.xml\n",
+        site_url
+    )
+}
+
+/// Date of a point in time, e.g. `2020-05-17`.
+pub fn date(time: SystemTime) -> Option<String> {
+    let days = time.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64 / 86_400;
+
+    // Days to civil date, from http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn robots_only_at_the_host_root() {
+        assert!(is_host_root("https://example.com"));
+        assert!(is_host_root("https://example.com/"));
+        assert!(!is_host_root("https://example.com/docs/"));
+        assert!(!is_host_root("https://example.com/docs"));
+    }
+}
@@ -1,5 +1,11 @@
+use crate::api::anchors::page_name;
+use crate::api::data::TocItem;
+use crate::api::engine::ApiConfig;
+use crate::api::inline::inline_assets;
 use crate::api::theme::HtmlTheme;
+use crate::api::util::is_absolute;
 use crate::api::HtmlContext;
+use crate::sink::Sink;
 use crate::template::Template;
 use crate::theme::Theme;
 
@@ -7,9 +13,8 @@ use handlebars::{Context, Handlebars, Helper, HelperDef, Output, RenderError};
 use pulldown_cmark::{html, Event, Parser};
 use regex::{Captures, Regex};
 use serde::Serialize;
-use std::cmp::Ordering;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use mdbook::book::BookItem;
 use mdbook::errors::Result;
@@ -29,42 +34,142 @@ where
         Ok(HtmlTemplate)
     }
 
-    fn render_chapter(
+    fn render_chapter<S: Sink>(
         &self,
         ctx: &RenderContext,
         theme: &Self::Theme,
         item: &mut HtmlContext,
         input: &mut I,
+        sink: &mut S,
     ) -> Result<()> {
-        if let Some(BookItem::Chapter(ref ch)) = &item.book_item {
-            let mut handlebars = Handlebars::new();
+        render_page(ctx, theme, item, input, sink, false)
+    }
+
+    fn render_page<S: Sink>(
+        &self,
+        ctx: &RenderContext,
+        theme: &Self::Theme,
+        item: &mut HtmlContext,
+        input: &mut I,
+        sink: &mut S,
+    ) -> Result<()> {
+        render_page(ctx, theme, item, input, sink, false)
+    }
+}
 
-            handlebars
-                .register_template_string("index", String::from_utf8(theme.get_template())?)?;
-            handlebars.register_helper("toc", Box::new(RenderToc));
+/// Render the template of the item into its own page. With `single_page`
+/// the chapters are in `index.html`, which the table of contents links to.
+fn render_page<I: Serialize, S: Sink>(
+    ctx: &RenderContext,
+    theme: &HtmlTheme,
+    item: &mut HtmlContext,
+    input: &mut I,
+    sink: &mut S,
+    single_page: bool,
+) -> Result<()> {
+    if let Some(BookItem::Chapter(ref ch)) = &item.book_item {
+        let mut handlebars = Handlebars::new();
 
-            let filepath = Path::new(&ch.path).with_extension("html");
+        // Chapters may select another template with their front matter
+        let template = match item.layout {
+            Some(ref layout) => theme
+                .get_layout(layout)
+                .ok_or_else(|| format!("Unknown layout `{}` in {}", layout, ch.path.display()))?
+                .to_owned(),
+            None => theme.get_template(),
+        };
+
+        handlebars.register_template_string("index", String::from_utf8(template)?)?;
+        handlebars.register_helper("toc", Box::new(RenderToc { single_page }));
+        handlebars.register_helper("asset", Box::new(AssetPath::new(theme)));
+        handlebars.register_helper("url", Box::new(UrlPath));
+
+        let filepath = if item.is_index {
+            PathBuf::from("index.html")
+        } else {
+            Path::new(&ch.path).with_extension("html")
+        };
 
-            // Render the handlebars template with the data
-            let rendered = handlebars.render("index", &input)?;
-            let rendered = fix_code_blocks(&rendered);
-            let rendered = fix_heading_ids(&rendered);
+        // Render the handlebars template with the data
+        let rendered = handlebars.render("index", &input)?;
+        let rendered = fix_code_blocks(&rendered);
+        let mut rendered = fix_heading_ids(&rendered);
 
-            // Write to file
-            if item.is_index {
-                utils::fs::write_file(&ctx.destination, "index.html", rendered.as_bytes())?;
-            } else {
-                utils::fs::write_file(&ctx.destination, &filepath, rendered.as_bytes())?;
-            }
+        if theme.is_self_contained() {
+            rendered = inline_assets(&rendered, &filepath, theme.assets(), &ctx.source_dir())?;
         }
 
-        Ok(())
+        // Write to file
+        sink.write(&filepath, rendered.as_bytes())?;
     }
+
+    Ok(())
 }
 
 /// One pager html template
+///
+/// The page is streamed: the template is rendered around a placeholder, the
+/// part before it is written first, then every chapter, then the part after.
+/// Layouts selected by the chapters are ignored, the page has one template.
 #[derive(Debug, Default)]
-pub struct HtmlOnePageTemplate;
+pub struct HtmlOnePageTemplate {
+    /// Shift the headings of nested chapters, see `output.api.shift_headings`
+    shift_headings: bool,
+}
+
+/// Stands for the content while rendering the one page template.
+const CONTENT_PLACEHOLDER: &str = "<mdbook-api-content/>";
+
+impl HtmlOnePageTemplate {
+    /// Render the template around the content placeholder, returning the head
+    /// and the tail of the page.
+    fn render_around_content<I: Serialize>(
+        &self,
+        theme: &HtmlTheme,
+        input: &I,
+    ) -> Result<(String, String)> {
+        let mut data = serde_json::to_value(input)?;
+        if let Some(data) = data.as_object_mut() {
+            data.insert("content".to_owned(), CONTENT_PLACEHOLDER.into());
+        }
+
+        let mut handlebars = Handlebars::new();
+        handlebars.register_template_string("index", String::from_utf8(theme.get_template())?)?;
+        handlebars.register_helper("toc", Box::new(RenderToc { single_page: true }));
+        handlebars.register_helper("asset", Box::new(AssetPath::new(theme)));
+        handlebars.register_helper("url", Box::new(UrlPath));
+
+        // Render the handlebars template with the data
+        let rendered = handlebars.render("index", &data)?;
+        let rendered = fix_code_blocks(&rendered);
+        let mut rendered = fix_heading_ids(&rendered);
+
+        match rendered.find(CONTENT_PLACEHOLDER) {
+            Some(index) => {
+                let tail = rendered.split_off(index);
+                Ok((rendered, tail[CONTENT_PLACEHOLDER.len()..].to_owned()))
+            }
+            None => {
+                warn!("The template doesn't render `content`, chapters are appended to the page");
+                Ok((rendered, String::new()))
+            }
+        }
+    }
+
+    fn inline_assets(
+        &self,
+        ctx: &RenderContext,
+        theme: &HtmlTheme,
+        html: String,
+    ) -> Result<String> {
+        if !theme.is_self_contained() {
+            return Ok(html);
+        }
+
+        let page = Path::new("index.html");
+        inline_assets(&html, page, theme.assets(), &ctx.source_dir())
+    }
+}
 
 impl<I> Template<HtmlContext, I> for HtmlOnePageTemplate
 where
@@ -72,21 +177,78 @@ where
 {
     type Theme = HtmlTheme;
 
-    fn load_from_context(_ctx: &RenderContext) -> Result<Self> {
-        Ok(HtmlOnePageTemplate)
+    fn load_from_context(ctx: &RenderContext) -> Result<Self> {
+        let api_config = ApiConfig::from_context(ctx);
+
+        Ok(HtmlOnePageTemplate {
+            shift_headings: api_config.shift_headings,
+        })
     }
 
-    fn finalize_book(&self, ctx: &RenderContext, theme: &Self::Theme, input: &mut I) -> Result<()> {
-        let mut handlebars = Handlebars::new();
-        handlebars.register_template_string("index", String::from_utf8(theme.get_template())?)?;
-        handlebars.register_helper("toc", Box::new(RenderToc));
+    fn is_single_page(&self) -> bool {
+        true
+    }
 
-        // Render the handlebars template with the data
-        let rendered = handlebars.render("index", &input)?;
-        let rendered = fix_code_blocks(&rendered);
-        let rendered = fix_heading_ids(&rendered);
+    fn initialize_book<S: Sink>(
+        &self,
+        ctx: &RenderContext,
+        theme: &Self::Theme,
+        input: &mut I,
+        sink: &mut S,
+    ) -> Result<()> {
+        let (head, _) = self.render_around_content(theme, input)?;
+        let head = self.inline_assets(ctx, theme, head)?;
+        sink.write(Path::new("index.html"), head.as_bytes())
+    }
 
-        utils::fs::write_file(&ctx.destination, "index.html", rendered.as_bytes())
+    fn render_chapter<S: Sink>(
+        &self,
+        ctx: &RenderContext,
+        theme: &Self::Theme,
+        item: &mut HtmlContext,
+        _input: &mut I,
+        sink: &mut S,
+    ) -> Result<()> {
+        let mut content = fix_code_blocks(&item.content);
+
+        if let Some(BookItem::Chapter(ref ch)) = item.book_item {
+            // Links to the other chapters target their heading in the page
+            let anchors = &item.book_state.anchors;
+            content = anchors.fix_single_page_links(&content, &ch.path);
+
+            if self.shift_headings {
+                // A chapter nested at depth 3 starts with a `<h3>`
+                let depth = ch.number.as_ref().map_or(1, |number| number.len());
+                content = shift_headings(&content, depth - 1);
+            }
+        }
+
+        let content = fix_heading_ids(&content);
+        let content = self.inline_assets(ctx, theme, content)?;
+        sink.append(Path::new("index.html"), content.as_bytes())
+    }
+
+    fn render_page<S: Sink>(
+        &self,
+        ctx: &RenderContext,
+        theme: &Self::Theme,
+        item: &mut HtmlContext,
+        input: &mut I,
+        sink: &mut S,
+    ) -> Result<()> {
+        render_page(ctx, theme, item, input, sink, true)
+    }
+
+    fn finalize_book<S: Sink>(
+        &self,
+        ctx: &RenderContext,
+        theme: &Self::Theme,
+        input: &mut I,
+        sink: &mut S,
+    ) -> Result<()> {
+        let (_, tail) = self.render_around_content(theme, input)?;
+        let tail = self.inline_assets(ctx, theme, tail)?;
+        sink.append(Path::new("index.html"), tail.as_bytes())
     }
 }
 
@@ -120,17 +282,43 @@ pub fn fix_code_blocks(html: &str) -> String {
         .into_owned()
 }
 
+/// Lower the level of every heading by `offset`, down to `<h6>`.
+pub fn shift_headings(html: &str, offset: usize) -> String {
+    lazy_static! {
+        static ref HEADING: Regex = Regex::new(r"<(/?)h([1-6])([ >])").unwrap();
+    }
+
+    if offset == 0 {
+        return html.to_owned();
+    }
+
+    HEADING
+        .replace_all(html, |caps: &Captures<'_>| {
+            let level: usize = caps[2].parse().unwrap_or(1);
+            format!("<{}h{}{}", &caps[1], (level + offset).min(6), &caps[3])
+        })
+        .into_owned()
+}
+
 pub fn fix_heading_ids(html: &str) -> String {
-    let regex = Regex::new(r##"<h([\d])>([^<]+)<"##).unwrap();
+    let regex = Regex::new(r##"<h([\d])((?: [^>]*)?)>([^<]+)<"##).unwrap();
     regex
         .replace_all(html, |caps: &Captures<'_>| {
             let level = &caps[1];
-            let title = &caps[2];
-            let id = utils::normalize_id(&title);
+            let attributes = &caps[2];
+            let title = &caps[3];
+
+            // Headings of the chapters already have their id
+            if attributes.contains(" id=") {
+                return caps[0].to_owned();
+            }
+
+            let id = utils::normalize_id(title);
 
             format!(
-                r#"<h{level} id="{id}">{title}<"#,
+                r#"<h{level}{attributes} id="{id}">{title}<"#,
                 level = level,
+                attributes = attributes,
                 id = id,
                 title = title,
             )
@@ -138,9 +326,127 @@ pub fn fix_heading_ids(html: &str) -> String {
         .into_owned()
 }
 
+// Handlebars helper returning the path of an asset from the current page, e.g.
+// `{{asset "app.css"}}`, taking fingerprinting into account
+pub struct AssetPath {
+    manifest: BTreeMap<String, String>,
+    // Assets are inlined from their path relative to the page, never from
+    // `site_url`
+    self_contained: bool,
+}
+
+impl AssetPath {
+    pub fn new(theme: &HtmlTheme) -> Self {
+        AssetPath {
+            manifest: theme.manifest().clone(),
+            self_contained: theme.is_self_contained(),
+        }
+    }
+}
+
+impl HelperDef for AssetPath {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut handlebars::RenderContext<'reg>,
+        out: &mut dyn Output,
+    ) -> std::result::Result<(), RenderError> {
+        let name = h
+            .param(0)
+            .and_then(|param| param.value().as_str())
+            .ok_or_else(|| RenderError::new("Param 0 with string type is required for asset"))?;
+
+        // Unknown names are kept as is, e.g. files from the book source
+        let name = self.manifest.get(name).map(String::as_str).unwrap_or(name);
+
+        if self.self_contained && !is_absolute(name) {
+            let path = rc.evaluate(ctx, "@root/path")?;
+            let path = path.as_json().as_str().unwrap_or("");
+            out.write(&format!("{}{}", utils::fs::path_to_root(path), name))?;
+            return Ok(());
+        }
+
+        out.write(&url_from_root(ctx, rc, name)?)?;
+        Ok(())
+    }
+}
+
+// Handlebars helper returning the URL of a path relative to the root of the
+// book from the current page, e.g. `{{url "users/list.html"}}`. URLs with a
+// scheme, absolute paths and fragments are kept as is.
+#[derive(Clone, Copy)]
+pub struct UrlPath;
+
+impl HelperDef for UrlPath {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        _r: &'reg Handlebars,
+        ctx: &'rc Context,
+        rc: &mut handlebars::RenderContext<'reg>,
+        out: &mut dyn Output,
+    ) -> std::result::Result<(), RenderError> {
+        let path = h
+            .param(0)
+            .and_then(|param| param.value().as_str())
+            .unwrap_or("");
+
+        out.write(&url_from_root(ctx, rc, path)?)?;
+        Ok(())
+    }
+}
+
+/// URL of a path relative to the root of the book from the current page.
+fn url_from_root<'reg: 'rc, 'rc>(
+    ctx: &'rc Context,
+    rc: &mut handlebars::RenderContext<'reg>,
+    path: &str,
+) -> std::result::Result<String, RenderError> {
+    if is_absolute(path) {
+        return Ok(path.to_owned());
+    }
+
+    let path_to_root = rc.evaluate(ctx, "@root/path_to_root")?;
+    let path_to_root = path_to_root.as_json().as_str().unwrap_or("");
+    Ok(format!("{}{}", path_to_root, path))
+}
+
 // Handlebars helper to construct TOC
 #[derive(Clone, Copy)]
-pub struct RenderToc;
+pub struct RenderToc {
+    /// Whether all the chapters are in `index.html`, otherwise the links to
+    /// the other chapters target their page
+    pub single_page: bool,
+}
+
+/// How the entries of the table of contents are written.
+struct TocOptions {
+    section_numbers: bool,
+    single_page: bool,
+    /// Path of the first chapter, rendered as `index.html`
+    index: Option<String>,
+    /// Current page, e.g. `users/list.html`
+    page: String,
+    path_to_root: String,
+}
+
+impl TocOptions {
+    fn link(&self, item: &TocItem) -> String {
+        let page = if self.single_page || self.index.as_ref() == Some(&item.path) {
+            "index.html".to_owned()
+        } else {
+            page_name(Path::new(&item.path))
+        };
+
+        if page == self.page {
+            format!("#{}", item.anchor)
+        } else {
+            format!("{}{}#{}", self.path_to_root, page, item.anchor)
+        }
+    }
+}
 
 impl HelperDef for RenderToc {
     fn call<'reg: 'rc, 'rc>(
@@ -155,101 +461,89 @@ impl HelperDef for RenderToc {
         // rc.get_path() is current json parent path, you should always use it like this
         // param is the key of value you want to display
         let chapters = rc.evaluate(ctx, "@root/chapters").and_then(|c| {
-            serde_json::value::from_value::<Vec<BTreeMap<String, String>>>(c.as_json().clone())
+            serde_json::value::from_value::<Vec<TocItem>>(c.as_json().clone())
                 .map_err(|_| RenderError::new("Could not decode the JSON data"))
         })?;
+        let section_numbers = rc.evaluate(ctx, "@root/section_numbers")?;
+        let path = rc.evaluate(ctx, "@root/path")?;
+        let path_to_root = rc.evaluate(ctx, "@root/path_to_root")?;
+
+        let options = TocOptions {
+            section_numbers: section_numbers.as_json().as_bool().unwrap_or(false),
+            single_page: self.single_page,
+            index: chapters
+                .first()
+                .filter(|item| !item.is_separator)
+                .map(|item| item.path.clone()),
+            page: page_name(Path::new(path.as_json().as_str().unwrap_or(""))),
+            path_to_root: path_to_root.as_json().as_str().unwrap_or("").to_owned(),
+        };
 
         out.write("<ul id=\"toc\" class=\"toc-list-h1\">")?;
+        render_toc_items(&chapters, &options, out)?;
+        out.write("</ul>")?;
+        Ok(())
+    }
+}
 
-        let mut current_level = 1;
-        let mut close = false;
-
-        for item in chapters {
-            let (_, level) = if let Some(s) = item.get("section") {
-                (s.as_str(), s.matches('.').count())
-            } else {
-                ("", 1)
-            };
-
-            match level.cmp(&current_level) {
-                Ordering::Less => {
-                    while level < current_level {
-                        out.write("</ul>")?;
-                        current_level -= 1;
-                    }
-                    out.write("<li>")?;
-                }
-                Ordering::Equal => {
-                    if close {
-                        out.write("</li>")?;
-                        close = false;
-                    }
-                    out.write("<li>")?;
-                }
-                Ordering::Greater => {
-                    while level > current_level {
-                        current_level += 1;
-                        out.write(&format!("<ul class=\"toc-list-h{}\">", level))?;
-                    }
-                    out.write("<li>")?;
+fn render_toc_items(
+    items: &[TocItem],
+    options: &TocOptions,
+    out: &mut dyn Output,
+) -> std::result::Result<(), RenderError> {
+    for item in items {
+        if item.is_hidden {
+            continue;
+        }
+
+        if item.is_separator {
+            out.write("<li class=\"toc-separator\" role=\"separator\"></li>")?;
+            continue;
+        }
+
+        out.write("<li>")?;
+        out.write(&format!("<a href=\"{}\" ", options.link(item)))?;
+
+        // Prefix and suffix chapters have no section number
+        match item.section {
+            Some(ref section) => {
+                out.write(&format!("class=\"toc-h{} toc-link\" ", item.level))?;
+
+                if options.section_numbers {
+                    out.write(&format!("data-section=\"{}\" ", section))?;
                 }
             }
-
-            if let Some(name) = item.get("name") {
-                //let ancor = if let Some(path) = item.get("path") {
-                //    if !path.is_empty() {
-                //        let tmp = Path::new(path)
-                //            .with_extension("");
-
-                //        tmp.as_path()
-                //            .file_name()
-                //            .unwrap()
-                //            .to_str()
-                //            .unwrap()
-                //            .to_owned()
-                //    } else {
-                //        "".to_owned()
-                //    }
-                //} else {
-                //    "".to_owned()
-                //};
-
-                out.write(&format!("<a href=\"#{}\" ", utils::normalize_id(name)))?;
-                out.write(&format!("class=\"toc-h{} toc-link\" ", level))?;
-
-                // Render only inline code blocks
-
-                // filter all events that are not inline code blocks
-                let parser = Parser::new(name).filter(|event| match *event {
-                    Event::Code(_) | Event::Html(_) | Event::Text(_) => true,
-                    _ => false,
-                });
-
-                // render markdown to html
-                let mut markdown_parsed_name = String::new();
-                html::push_html(&mut markdown_parsed_name, parser);
-
-                // write to the handlebars template
-                out.write(&format!("data-title=\"{}\">", name))?;
-                out.write(&markdown_parsed_name)?;
-
-                out.write("</a>")?;
-                close = true;
+            None => {
+                out.write(&format!(
+                    "class=\"toc-h{} toc-link toc-unnumbered\" ",
+                    item.level
+                ))?;
             }
         }
 
-        while current_level > 1 {
-            if close {
-                out.write("</li>")?;
-                close = false;
-            }
+        // Render only inline code blocks
+
+        // filter all events that are not inline code blocks
+        let parser = Parser::new(&item.name)
+            .filter(|event| matches!(*event, Event::Code(_) | Event::Html(_) | Event::Text(_)));
+
+        // render markdown to html
+        let mut markdown_parsed_name = String::new();
+        html::push_html(&mut markdown_parsed_name, parser);
 
+        // write to the handlebars template
+        out.write(&format!("data-title=\"{}\">", item.name))?;
+        out.write(&markdown_parsed_name)?;
+        out.write("</a>")?;
+
+        if item.has_sub_items {
+            out.write(&format!("<ul class=\"toc-list-h{}\">", item.level + 1))?;
+            render_toc_items(&item.sub_items, options, out)?;
             out.write("</ul>")?;
-            out.write("</li>")?;
-            current_level -= 1;
         }
 
-        out.write("</ul>")?;
-        Ok(())
+        out.write("</li>")?;
     }
+
+    Ok(())
 }
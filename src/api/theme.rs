@@ -1,20 +1,26 @@
 use crate::api::engine::ApiConfig;
+use crate::api::prism;
+use crate::api::util::hex;
+use crate::sink::Sink;
 use crate::theme::Theme;
 
-use std::collections::HashMap;
-use std::fs::File;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
 use std::io::Read;
 use std::path::Path;
 
 use mdbook::errors::Result;
+use mdbook::errors::ResultExt;
 use mdbook::renderer::RenderContext;
-use mdbook::utils::fs::write_file;
 
 static INDEX: &[u8] = include_bytes!("../../theme/index.hbs");
 static FAVICON: &[u8] = include_bytes!("../../theme/favicon.png");
 static APP_CSS: &[u8] = include_bytes!("../../theme/app.css");
 static NORD_CSS: &[u8] = include_bytes!("../../theme/prism-nord.css");
+static ONE_LIGHT_CSS: &[u8] = include_bytes!("../../theme/prism-one-light.css");
 static APP_JS: &[u8] = include_bytes!("../../theme/app.js");
+static API_JS: &[u8] = include_bytes!("../../theme/api.js");
 static LOGO: &[u8] = include_bytes!("../../theme/logo.png");
 static NAVBAR: &[u8] = include_bytes!("../../theme/navbar.png");
 
@@ -27,7 +33,12 @@ static FONT_WOFF2: &[u8] = include_bytes!("../../theme/fonts/slate.woff2");
 #[derive(Debug)]
 pub struct HtmlTheme {
     template: Vec<u8>,
+    /// Templates selected with the `layout` front matter key, by file name
+    layouts: BTreeMap<String, Vec<u8>>,
     assets: HashMap<String, Vec<u8>>,
+    manifest: BTreeMap<String, String>,
+    self_contained: bool,
+    fingerprint: bool,
 }
 
 impl HtmlTheme {
@@ -36,7 +47,9 @@ impl HtmlTheme {
         assets_map.insert("favicon.png".to_owned(), FAVICON.to_owned());
         assets_map.insert("app.css".to_owned(), APP_CSS.to_owned());
         assets_map.insert("prism-nord.css".to_owned(), NORD_CSS.to_owned());
+        assets_map.insert("prism-one-light.css".to_owned(), ONE_LIGHT_CSS.to_owned());
         assets_map.insert("app.js".to_owned(), APP_JS.to_owned());
+        assets_map.insert("api.js".to_owned(), API_JS.to_owned());
         assets_map.insert("logo.png".to_owned(), LOGO.to_owned());
         assets_map.insert("navbar.png".to_owned(), NAVBAR.to_owned());
 
@@ -47,6 +60,27 @@ impl HtmlTheme {
         assets_map.insert("fonts/slate.woff2".to_owned(), FONT_WOFF2.to_owned());
         assets_map
     }
+
+    pub fn assets(&self) -> &HashMap<String, Vec<u8>> {
+        &self.assets
+    }
+
+    /// Template of a layout, given by name without the `.hbs` extension
+    pub fn get_layout(&self, name: &str) -> Option<&[u8]> {
+        self.layouts
+            .get(&format!("{}.hbs", name))
+            .map(Vec::as_slice)
+    }
+
+    /// Whether the assets are inlined in the pages instead of being copied
+    pub fn is_self_contained(&self) -> bool {
+        self.self_contained
+    }
+
+    /// Map the logical name of each asset to the name it is written with
+    pub fn manifest(&self) -> &BTreeMap<String, String> {
+        &self.manifest
+    }
 }
 
 // TODO add assets present in theme folder, like imgs
@@ -54,32 +88,32 @@ impl HtmlTheme {
 impl Theme for HtmlTheme {
     /// Load a HTML theme from a render context
     fn load_from_context(ctx: &RenderContext) -> Result<Self> {
-        let config = &ctx.config;
-        let api_config: ApiConfig = match config.get_deserialized_opt("output.api") {
-            Ok(Some(config)) => Some(config),
-            _ => None,
-        }
-        .unwrap_or_default();
+        let api_config = ApiConfig::from_context(ctx);
 
         let mut assets = Self::load_default_assests();
         let mut template = INDEX.to_owned();
+        let mut layouts = BTreeMap::new();
+
+        // Bundle Prism with the grammars of the menu and code blocks languages
+        let mut languages = prism::book_languages(&ctx.book);
+        languages.extend(api_config.lang.iter().map(|lang| lang.id.clone()));
+        assets.insert(
+            "prism.js".to_owned(),
+            prism::bundle(languages.iter().map(String::as_str)),
+        );
 
-        let theme_dir = if let Some(path) = api_config.theme_dir {
-            ctx.root.join(Path::new(&path).to_path_buf())
-        } else {
-            ctx.root.join("theme")
-        };
+        let theme_dir = api_config.theme_dir(&ctx.root);
 
         if theme_dir.exists() && theme_dir.is_dir() {
             // Overload assets if present in theme_dir
-            for (name, mut content) in &mut assets {
+            for (name, content) in &mut assets {
                 let filename = theme_dir.join(name);
 
                 if !filename.exists() {
                     continue;
                 }
 
-                load_file_contents(&filename, &mut content)?;
+                load_file_contents(&filename, content)?;
             }
 
             // Overload index template
@@ -88,22 +122,83 @@ impl Theme for HtmlTheme {
             if filename.exists() {
                 load_file_contents(&filename, &mut template)?;
             }
+
+            // Any other template is a layout
+            let entries = fs::read_dir(&theme_dir)
+                .chain_err(|| format!("Unable to read {}", theme_dir.display()))?;
+
+            for entry in entries {
+                let path = entry?.path();
+                let name = match path.file_name().and_then(|name| name.to_str()) {
+                    Some(name) if name.ends_with(".hbs") && name != "index.hbs" => name,
+                    _ => continue,
+                };
+
+                let mut content = vec![];
+                load_file_contents(&path, &mut content)?;
+                layouts.insert(name.to_owned(), content);
+            }
         }
 
-        Ok(HtmlTheme { template, assets })
+        // Other palettes are read from the theme folder
+        let code_theme = api_config.code_theme.stylesheets();
+        for name in &[code_theme.light, code_theme.dark] {
+            if assets.contains_key(name) {
+                continue;
+            }
+
+            let filename = theme_dir.join(name);
+            if !filename.exists() {
+                return Err(format!("Unknown code theme, {} is not in the theme", name).into());
+            }
+
+            let mut content = vec![];
+            load_file_contents(&filename, &mut content)?;
+            assets.insert(name.clone(), content);
+        }
+
+        // Inlined assets are looked up by their logical name
+        let fingerprint = api_config.fingerprint && !api_config.self_contained;
+
+        let manifest = assets
+            .iter()
+            .map(|(name, content)| {
+                let output = if fingerprint {
+                    fingerprinted_name(name, content)
+                } else {
+                    name.clone()
+                };
+                (name.clone(), output)
+            })
+            .collect();
+
+        Ok(HtmlTheme {
+            template,
+            layouts,
+            assets,
+            manifest,
+            self_contained: api_config.self_contained,
+            fingerprint,
+        })
     }
 
-    fn copy_static_files(&self, ctx: &RenderContext) -> Result<()> {
-        let destination = &ctx.destination;
+    fn copy_static_files<S: Sink>(&self, _ctx: &RenderContext, sink: &mut S) -> Result<()> {
+        if self.self_contained {
+            return Ok(());
+        }
 
-        write_file(
-            destination,
-            ".nojekyll",
+        sink.write(
+            Path::new(".nojekyll"),
             b"This file makes sure that Github Pages doesn't process mdBook's output.",
         )?;
 
         for (name, content) in &self.assets {
-            write_file(destination, name, &content)?;
+            sink.write(Path::new(&self.manifest[name]), content)?;
+        }
+
+        if self.fingerprint {
+            let manifest = serde_json::to_vec_pretty(&self.manifest)?;
+            sink.write(Path::new("asset-manifest.json"), &manifest)?;
         }
 
         Ok(())
@@ -112,6 +207,33 @@ impl Theme for HtmlTheme {
     fn get_template(&self) -> Vec<u8> {
         self.template.clone()
     }
+
+    fn get_assets(&self) -> Vec<(&str, &[u8])> {
+        let mut assets: Vec<_> = self
+            .assets
+            .iter()
+            .chain(&self.layouts)
+            .map(|(name, content)| (name.as_str(), content.as_slice()))
+            .collect();
+        assets.sort();
+        assets
+    }
+}
+
+/// Insert the first hex digits of the content hash before the extension, e.g.
+/// `app.3f2a9c1d.css`. Fonts are referenced by the stylesheets and keep their
+/// name.
+fn fingerprinted_name(name: &str, content: &[u8]) -> String {
+    if name.starts_with("fonts/") {
+        return name.to_owned();
+    }
+
+    let hash = hex(&Sha256::digest(content)[..4]);
+
+    match name.rfind('.') {
+        Some(dot) => format!("{}.{}{}", &name[..dot], hash, &name[dot..]),
+        None => format!("{}.{}", name, hash),
+    }
 }
 
 /// Checks if a file exists, if so, the destination buffer will be filled with
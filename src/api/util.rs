@@ -0,0 +1,62 @@
+//! Small helpers shared by the modules of the renderer.
+
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+use mdbook::errors::Result;
+
+/// Lowercase hexadecimal encoding of `bytes`.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Escape a text or an attribute value of an HTML or XML document.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// JSON of `value` to be written in a single quoted HTML attribute.
+pub fn json_attribute<T: Serialize>(value: &T) -> Result<String> {
+    Ok(serde_json::to_string(value)?.replace('\'', "\\u0027"))
+}
+
+/// Whether a URL starts with a scheme, e.g. `https:` or `mailto:`.
+pub fn has_scheme(url: &str) -> bool {
+    lazy_static! {
+        static ref SCHEME: Regex = Regex::new(r"^[a-z][a-z0-9+.-]*:").unwrap();
+    }
+
+    SCHEME.is_match(url)
+}
+
+/// Whether a URL doesn't depend on the page it is used in: it has a scheme,
+/// starts from the root of the host or is a fragment.
+pub fn is_absolute(url: &str) -> bool {
+    has_scheme(url) || url.starts_with('/') || url.starts_with('#')
+}
+
+/// Resolve a link found in a file located in `base`, ignoring external links,
+/// queries and fragments. Returns an empty path for external links.
+pub fn resolve_path(base: &Path, link: &str) -> PathBuf {
+    if has_scheme(link) || link.starts_with("//") {
+        return PathBuf::new();
+    }
+
+    let link = link.split(['?', '#']).next().unwrap_or("");
+
+    let mut path = PathBuf::new();
+    for component in base.join(link).components() {
+        match component {
+            Component::ParentDir => {
+                path.pop();
+            }
+            Component::Normal(part) => path.push(part),
+            _ => {}
+        }
+    }
+    path
+}
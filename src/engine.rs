@@ -13,6 +13,12 @@ pub trait Engine<C>: Sized {
 
     fn load_from_context(ctx: &RenderContext) -> Result<Self>;
 
+    /// Data of the book before its chapters are rendered. Defaults to the data
+    /// of `finalize_book`.
+    fn initialize_book(&self, ctx: &RenderContext, item: &mut C) -> Result<Self::Output> {
+        self.finalize_book(ctx, item)
+    }
+
     fn process_chapter(&self, ctx: &RenderContext, item: &mut C) -> Result<Self::Output>;
 
     fn finalize_book(&self, ctx: &RenderContext, item: &mut C) -> Result<Self::Output>;
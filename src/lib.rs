@@ -1,10 +1,11 @@
 #[macro_use]
-extern crate serde_json;
-#[macro_use]
 extern crate lazy_static;
+#[macro_use]
+extern crate log;
 
 pub mod api;
 pub mod engine;
+pub mod sink;
 pub mod template;
 pub mod theme;
 
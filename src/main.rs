@@ -1,14 +1,109 @@
-use mdbook_api::ApiOnePageRenderer;
+use mdbook_api::api::engine::ApiConfig;
+use mdbook_api::api::{BookState, HtmlContext};
+use mdbook_api::engine::Engine;
+use mdbook_api::{ApiOnePageRenderer, ApiRenderer, HtmlEngine};
 
+use mdbook::book::BookItem;
 use mdbook::renderer::RenderContext;
+use mdbook::MDBook;
+use std::env;
 use std::io;
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
 
 fn main() {
+    env_logger::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let mut args = env::args().skip(1);
+
+    if let Some("print-context") = args.next().as_deref() {
+        print_context(args);
+        return;
+    }
+
     // Get the mdbook context from stdin
     let mut stdin = io::stdin();
     let ctx = RenderContext::from_json(&mut stdin).unwrap();
 
-    // Render the API documentation with the ApiRenderer
-    let renderer = ApiOnePageRenderer::new(&ctx).expect("Failed to load renderer");
-    renderer.render(&ctx).expect("Failed to render");
+    // Render the API documentation with the renderer selected by the book
+    if ApiConfig::from_context(&ctx).multi_page {
+        let renderer = ApiRenderer::new(&ctx).expect("Failed to load renderer");
+        renderer.render(&ctx).expect("Failed to render");
+    } else {
+        let renderer = ApiOnePageRenderer::new(&ctx).expect("Failed to load renderer");
+        renderer.render(&ctx).expect("Failed to render");
+    }
+}
+
+/// Print the template data of a chapter, or of the whole book when no chapter
+/// path is given, as pretty JSON.
+///
+/// Usage: `mdbook-api print-context [--dir <book-dir>] [chapter.md]`
+fn print_context<I: Iterator<Item = String>>(mut args: I) {
+    let mut dir = ".".to_owned();
+    let mut chapter = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dir" => match args.next() {
+                Some(path) => dir = path,
+                None => usage(),
+            },
+            _ if chapter.is_none() && !arg.starts_with("--") => chapter = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let md = MDBook::load(dir).expect("Failed to load book");
+    let destination = md.build_dir_for("api");
+    let ctx = RenderContext::new(md.root, md.book, md.config, destination);
+
+    let engine = HtmlEngine::load_from_context(&ctx).expect("Failed to load engine");
+
+    // Chapters streamed into one page have no page data of their own, the
+    // data of their page in the multi-page output is printed
+    let api_config = ApiConfig::from_context(&ctx);
+    let single_page = !api_config.multi_page && chapter.is_none();
+    let book_state = BookState::new(&ctx, &api_config, single_page).expect("Failed to load book");
+    let mut html_ctx = HtmlContext {
+        is_index: true,
+        book_state: Arc::new(book_state),
+        ..Default::default()
+    };
+
+    let path = match chapter {
+        Some(path) => path,
+        None => {
+            let data = engine
+                .initialize_book(&ctx, &mut html_ctx)
+                .expect("Failed to initialize book");
+            println!("{}", serde_json::to_string_pretty(&data).unwrap());
+            return;
+        }
+    };
+
+    for item in ctx.book.iter() {
+        if let BookItem::Chapter(ref ch) = *item {
+            if ch.path == Path::new(&path) {
+                html_ctx.book_item = Some(item.clone());
+
+                let data = engine
+                    .process_chapter(&ctx, &mut html_ctx)
+                    .expect("Failed to process chapter");
+                println!("{}", serde_json::to_string_pretty(&data).unwrap());
+                return;
+            }
+        }
+
+        html_ctx.is_index = false;
+    }
+
+    eprintln!("No chapter found for {}", path);
+    process::exit(1);
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: mdbook-api print-context [--dir <book-dir>] [chapter.md]");
+    process::exit(1);
 }
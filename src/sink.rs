@@ -0,0 +1,210 @@
+//! A sink receives the files generated by a renderer. It decouples the rendering
+//! from the place where the output ends up, e.g. the filesystem, memory or an
+//! archive.
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+
+use mdbook::errors::Result;
+use mdbook::errors::ResultExt;
+use mdbook::utils::fs::write_file;
+
+pub trait Sink {
+    /// Value returned once all the files have been written
+    type Output;
+
+    /// Write a file at `path`, relative to the root of the output.
+    fn write(&mut self, path: &Path, content: &[u8]) -> Result<()>;
+
+    /// Append to the file at `path`, which must be the last one written.
+    fn append(&mut self, path: &Path, content: &[u8]) -> Result<()>;
+
+    /// Consume the sink once the rendering is done.
+    fn finish(self) -> Result<Self::Output>;
+}
+
+/// Write files into a destination folder.
+#[derive(Debug)]
+pub struct FsSink {
+    destination: PathBuf,
+}
+
+impl FsSink {
+    pub fn new<P: Into<PathBuf>>(destination: P) -> Self {
+        FsSink {
+            destination: destination.into(),
+        }
+    }
+}
+
+impl Sink for FsSink {
+    type Output = ();
+
+    fn write(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        write_file(&self.destination, path, content)
+    }
+
+    fn append(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        let filename = self.destination.join(path);
+
+        OpenOptions::new()
+            .append(true)
+            .open(&filename)
+            .and_then(|mut file| file.write_all(content))
+            .chain_err(|| format!("Unable to append to {}", filename.display()))
+    }
+
+    fn finish(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Keep files in memory, indexed by their path.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        MemorySink::default()
+    }
+}
+
+impl Sink for MemorySink {
+    type Output = BTreeMap<PathBuf, Vec<u8>>;
+
+    fn write(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        self.files.insert(path.to_path_buf(), content.to_owned());
+        Ok(())
+    }
+
+    fn append(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        self.files
+            .entry(path.to_path_buf())
+            .or_default()
+            .extend_from_slice(content);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self::Output> {
+        Ok(self.files)
+    }
+}
+
+/// Write files into a zip archive.
+pub struct ZipSink<W: Write + Seek> {
+    archive: zip::ZipWriter<W>,
+    current: Option<PathBuf>,
+}
+
+impl<W: Write + Seek> ZipSink<W> {
+    pub fn new(writer: W) -> Self {
+        ZipSink {
+            archive: zip::ZipWriter::new(writer),
+            current: None,
+        }
+    }
+}
+
+impl<W: Write + Seek> Sink for ZipSink<W> {
+    type Output = W;
+
+    fn write(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        self.archive
+            .start_file(archive_path(path)?, options)
+            .chain_err(|| "Unable to add file to zip archive")?;
+        self.archive.write_all(content)?;
+        self.current = Some(path.to_path_buf());
+        Ok(())
+    }
+
+    fn append(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        if self.current.as_deref() != Some(path) {
+            return Err(format!("Unable to append to {} in zip archive", path.display()).into());
+        }
+
+        self.archive.write_all(content)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<W> {
+        self.archive
+            .finish()
+            .chain_err(|| "Unable to finish zip archive")
+    }
+}
+
+/// Write files into a tar archive.
+///
+/// Entries need their size upfront, the last file is kept in memory until the
+/// next one is written in case it is appended to.
+pub struct TarSink<W: Write> {
+    archive: tar::Builder<W>,
+    current: Option<(PathBuf, Vec<u8>)>,
+}
+
+impl<W: Write> TarSink<W> {
+    pub fn new(writer: W) -> Self {
+        TarSink {
+            archive: tar::Builder::new(writer),
+            current: None,
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some((path, content)) = self.current.take() {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+
+            self.archive
+                .append_data(&mut header, archive_path(&path)?, content.as_slice())
+                .chain_err(|| "Unable to add file to tar archive")?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Sink for TarSink<W> {
+    type Output = W;
+
+    fn write(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        self.flush()?;
+        self.current = Some((path.to_path_buf(), content.to_owned()));
+        Ok(())
+    }
+
+    fn append(&mut self, path: &Path, content: &[u8]) -> Result<()> {
+        match self.current {
+            Some((ref current, ref mut buffer)) if current == path => {
+                buffer.extend_from_slice(content);
+                Ok(())
+            }
+            _ => Err(format!("Unable to append to {} in tar archive", path.display()).into()),
+        }
+    }
+
+    fn finish(mut self) -> Result<W> {
+        self.flush()?;
+        self.archive
+            .into_inner()
+            .chain_err(|| "Unable to finish tar archive")
+    }
+}
+
+/// Archives always use `/` as separator, whatever the platform.
+fn archive_path(path: &Path) -> Result<String> {
+    let parts = path
+        .iter()
+        .map(|part| part.to_str().chain_err(|| "Could not convert path to str"))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(parts.join("/"))
+}
@@ -6,6 +6,7 @@ use serde::Serialize;
 use mdbook::errors::Result;
 use mdbook::renderer::RenderContext;
 
+use crate::sink::Sink;
 use crate::theme::Theme;
 
 pub trait Template<C, I: Serialize>: Sized {
@@ -13,25 +14,52 @@ pub trait Template<C, I: Serialize>: Sized {
 
     fn load_from_context(ctx: &RenderContext) -> Result<Self>;
 
-    fn initialize_book(&self, _ctx: &RenderContext, _theme: &Self::Theme) -> Result<()> {
+    /// Whether all the chapters are rendered into a single page, which then
+    /// changes whenever one of them changes.
+    fn is_single_page(&self) -> bool {
+        false
+    }
+
+    fn initialize_book<S: Sink>(
+        &self,
+        _ctx: &RenderContext,
+        _theme: &Self::Theme,
+        _input: &mut I,
+        _sink: &mut S,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn render_chapter<S: Sink>(
+        &self,
+        _ctx: &RenderContext,
+        _theme: &Self::Theme,
+        _item: &mut C,
+        _input: &mut I,
+        _sink: &mut S,
+    ) -> Result<()> {
         Ok(())
     }
 
-    fn render_chapter(
+    /// Render a complete page outside of the chapters flow, e.g. the page
+    /// served for missing paths.
+    fn render_page<S: Sink>(
         &self,
         _ctx: &RenderContext,
         _theme: &Self::Theme,
         _item: &mut C,
         _input: &mut I,
+        _sink: &mut S,
     ) -> Result<()> {
         Ok(())
     }
 
-    fn finalize_book(
+    fn finalize_book<S: Sink>(
         &self,
         _ctx: &RenderContext,
         _theme: &Self::Theme,
         _input: &mut I,
+        _sink: &mut S,
     ) -> Result<()> {
         Ok(())
     }
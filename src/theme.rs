@@ -7,6 +7,8 @@
 use mdbook::errors::Result;
 use mdbook::renderer::RenderContext;
 
+use crate::sink::Sink;
+
 // TODO add sass support
 // TODO add theme extension support
 // TODO add font Awsome support
@@ -14,7 +16,10 @@ use mdbook::renderer::RenderContext;
 pub trait Theme: Sized {
     fn load_from_context(ctx: &RenderContext) -> Result<Self>;
 
-    fn copy_static_files(&self, ctx: &RenderContext) -> Result<()>;
+    fn copy_static_files<S: Sink>(&self, ctx: &RenderContext, sink: &mut S) -> Result<()>;
 
     fn get_template(&self) -> Vec<u8>;
+
+    /// Assets and extra templates used by the rendered pages, by name
+    fn get_assets(&self) -> Vec<(&str, &[u8])>;
 }
@@ -0,0 +1,150 @@
+mod common;
+
+use common::{TempBook, LOCALES};
+use mdbook_api::ApiRenderer;
+use std::fs;
+
+fn build(book: &TempBook) -> mdbook::errors::Result<()> {
+    let ctx = book.context();
+    ApiRenderer::new(&ctx)?.render(&ctx)
+}
+
+#[test]
+fn removed_anchors_against_the_previous_build() {
+    let book = TempBook::new("removed-anchors");
+    book.configure("removed_anchors = \"fail\"");
+    build(&book).unwrap();
+    assert!(book.output("anchors.json").contains("\"errors\""));
+
+    let list = "# List\n\n## Failures\n\nListing may fail.\n";
+    book.write("src/users/list.md", list);
+    assert!(build(&book).is_err());
+
+    // The failed build left the previous output
+    book.configure(
+        "[output.api.redirects]\n\"users/list.html#errors\" = \"users/list.html#failures\"",
+    );
+    build(&book).unwrap();
+    assert!(book.output("anchors.json").contains("\"failures\""));
+}
+
+#[test]
+fn removed_anchors_are_only_reported_by_warn_and_ignore() {
+    for mode in &["warn", "ignore"] {
+        let book = TempBook::new(&format!("removed-anchors-{}", mode));
+        book.configure(&format!("removed_anchors = \"{}\"", mode));
+        build(&book).unwrap();
+
+        book.write("src/users/list.md", "# List\n");
+        build(&book).unwrap();
+    }
+}
+
+#[test]
+fn redirects_to_unknown_anchors_fail_the_first_build() {
+    let book = TempBook::new("unknown-redirect");
+    book.configure(
+        "removed_anchors = \"fail\"\n[output.api.redirects]\n\"users/list.html#old\" = \"users/list.html#missing\"",
+    );
+    assert!(build(&book).is_err());
+
+    let book = TempBook::new("known-redirect");
+    book.configure(
+        "removed_anchors = \"fail\"\n[output.api.redirects]\n\"users/list.html#old\" = \"users/list.html#errors\"",
+    );
+    build(&book).unwrap();
+    let list = book.output("users/list.html");
+    assert!(list.contains(r##"data-anchor-redirects='{"old":"#errors"}'"##));
+}
+
+#[test]
+fn editions_share_the_root() {
+    let book = TempBook::from_fixture(LOCALES, "editions");
+    build(&book).unwrap();
+
+    assert!(book.output("index.html").contains("en/index.html"));
+    assert!(book.output("404.html").contains("Page not found"));
+    assert!(book
+        .output("robots.txt")
+        .contains("https://example.com/sitemap.xml"));
+    let sitemap = book.output("sitemap.xml");
+    assert!(sitemap.contains("<loc>https://example.com/en/sitemap.xml</loc>"));
+    assert!(sitemap.contains("<loc>https://example.com/ja/sitemap.xml</loc>"));
+
+    let intro = book.output("ja/index.html");
+    assert!(intro.contains("フィクスチャ API へようこそ。"));
+    assert!(intro.contains(r#"<html lang="ja""#));
+    // Untranslated chapters are shown from the default edition
+    let users = book.output("ja/users.html");
+    assert!(users.contains("Users may not be found."));
+    assert!(users.contains("このページはまだ翻訳されていないため"));
+    assert!(book
+        .output("en/users.html")
+        .contains(r#"href="../ja/users.html""#));
+}
+
+#[test]
+fn editions_with_a_not_found_chapter() {
+    let book = TempBook::from_fixture(LOCALES, "editions-404-chapter");
+    book.write("src/en/404.md", "# Lost\n\nNothing here.\n")
+        .write(
+            "src/en/SUMMARY.md",
+            "# Summary\n\n- [Introduction](intro.md)\n- [Users](users.md)\n- [Lost](404.md)\n",
+        );
+    build(&book).unwrap();
+
+    assert!(book.output("en/404.html").contains("Nothing here."));
+    assert!(!book.root.join("book/404.html").exists());
+}
+
+#[test]
+fn incremental_editions_keep_their_cache() {
+    let book = TempBook::from_fixture(LOCALES, "editions-incremental");
+    book.configure("incremental = true");
+    build(&book).unwrap();
+    assert!(book.root.join("book/.en-cache.json").exists());
+    assert!(book.root.join("book/.ja-cache.json").exists());
+
+    // Unchanged pages are not written again
+    let users = book.root.join("book/ja/users.html");
+    fs::write(&users, "<!-- kept -->").unwrap();
+    book.write("src/ja/intro.md", "# はじめに\n\nようこそ。\n");
+    build(&book).unwrap();
+
+    assert_eq!(book.output("ja/users.html"), "<!-- kept -->");
+    assert!(book.output("ja/index.html").contains("ようこそ。"));
+    assert!(book.output("sitemap.xml").contains("ja/sitemap.xml"));
+}
+
+#[test]
+fn incremental_build_renders_the_changed_chapters() {
+    let book = TempBook::new("incremental");
+    book.configure("incremental = true\nsite_url = \"https://example.com/\"");
+    build(&book).unwrap();
+
+    let orders = book.root.join("book/orders.html");
+    let users = book.root.join("book/users.html");
+    fs::write(&orders, "<!-- kept -->").unwrap();
+    fs::write(&users, "<!-- kept -->").unwrap();
+
+    // Nothing changed, the sitemap is still written
+    fs::remove_file(book.root.join("book/sitemap.xml")).unwrap();
+    build(&book).unwrap();
+    assert_eq!(book.output("users.html"), "<!-- kept -->");
+    assert!(book
+        .output("sitemap.xml")
+        .contains("https://example.com/users.html"));
+
+    book.write(
+        "src/users.md",
+        "# Users\n\nManage the users.\n\n## Errors\n\nUsers may not be found.\n",
+    );
+    build(&book).unwrap();
+    assert!(book.output("users.html").contains("Manage the users."));
+    assert_eq!(book.output("orders.html"), "<!-- kept -->");
+
+    // A change of the configuration renders the whole book
+    book.configure("section_numbers = true");
+    build(&book).unwrap();
+    assert!(book.output("orders.html").contains("Orders of the users."));
+}
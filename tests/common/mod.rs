@@ -0,0 +1,90 @@
+// Each test file only uses some of the helpers
+#![allow(dead_code)]
+
+use mdbook::renderer::RenderContext;
+use mdbook::MDBook;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/book");
+/// The fixture book in English and Japanese, see `output.api.locale`
+pub const LOCALES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/locales");
+
+/// A copy of a fixture book in a temporary folder, to change its files and
+/// configuration before rendering it.
+pub struct TempBook {
+    pub root: PathBuf,
+}
+
+impl TempBook {
+    /// Copy the fixture book, `name` must be unique among the tests
+    pub fn new(name: &str) -> Self {
+        Self::from_fixture(FIXTURE, name)
+    }
+
+    pub fn from_fixture(fixture: &str, name: &str) -> Self {
+        let root = env::temp_dir().join(format!("mdbook-api-{}", name));
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        copy_dir(Path::new(fixture), &root);
+
+        TempBook { root }
+    }
+
+    /// Write a file of the book, relative to its root
+    pub fn write(&self, path: &str, content: &str) -> &Self {
+        let path = self.root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    /// Add lines to the `output.api` table, the last one of `book.toml`
+    pub fn configure(&self, lines: &str) -> &Self {
+        let path = self.root.join("book.toml");
+        let config = fs::read_to_string(&path).unwrap();
+        fs::write(path, format!("{}\n{}\n", config, lines)).unwrap();
+        self
+    }
+
+    /// Load the book, it renders into its `book` folder
+    pub fn context(&self) -> RenderContext {
+        let md = MDBook::load(&self.root).unwrap();
+        let destination = md.build_dir_for("api");
+        RenderContext::new(md.root, md.book, md.config, destination)
+    }
+
+    /// Read a file rendered on disk, relative to the destination
+    pub fn output(&self, path: &str) -> String {
+        let path = self.root.join("book").join(path);
+        fs::read_to_string(&path).unwrap_or_else(|_| panic!("{} is not rendered", path.display()))
+    }
+}
+
+pub fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+
+    for entry in fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let target = to.join(path.file_name().unwrap());
+
+        if path.is_dir() {
+            // The output of an earlier render of the fixture
+            if path.file_name().unwrap() != "book" {
+                copy_dir(&path, &target);
+            }
+        } else {
+            fs::copy(&path, &target).unwrap();
+        }
+    }
+}
+
+pub fn page(files: &BTreeMap<PathBuf, Vec<u8>>, path: &str) -> String {
+    let content = files
+        .get(Path::new(path))
+        .unwrap_or_else(|| panic!("{} is not rendered", path));
+    String::from_utf8(content.clone()).unwrap()
+}
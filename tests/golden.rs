@@ -0,0 +1,92 @@
+//! Compare the renders of the fixture book with the expected output committed
+//! in `tests/fixtures/golden`. After an intended change of the output, write
+//! it again with `MDBOOK_API_BLESS=1 cargo test --test golden`.
+
+mod common;
+
+use common::FIXTURE;
+use mdbook::renderer::RenderContext;
+use mdbook::MDBook;
+use mdbook_api::sink::MemorySink;
+use mdbook_api::{ApiOnePageRenderer, ApiRenderer};
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/golden");
+
+fn context() -> RenderContext {
+    let md = MDBook::load(FIXTURE).unwrap();
+    let destination = md.build_dir_for("api");
+    RenderContext::new(md.root, md.book, md.config, destination)
+}
+
+/// Check the generated files against `golden/<name>`: the list of their paths,
+/// and the content of the files generated from the book. Theme assets are
+/// copied as is and only listed.
+fn check(name: &str, files: &BTreeMap<PathBuf, Vec<u8>>) {
+    let dir = Path::new(GOLDEN).join(name);
+    let bless = env::var_os("MDBOOK_API_BLESS").is_some();
+
+    let mut expected = vec![("files.txt".into(), list(files))];
+    for (path, content) in files {
+        let generated = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("html") | Some("json") | Some("xml")
+        );
+        if generated {
+            expected.push((path.clone(), content.clone()));
+        }
+    }
+
+    if bless {
+        if dir.exists() {
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        for (path, content) in &expected {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        return;
+    }
+
+    for (path, content) in &expected {
+        let golden = fs::read(dir.join(path))
+            .unwrap_or_else(|_| panic!("{}/{} is missing", name, path.display()));
+        assert!(
+            golden == *content,
+            "{}/{} differs from the render, run with MDBOOK_API_BLESS=1 to update it",
+            name,
+            path.display()
+        );
+    }
+}
+
+fn list(files: &BTreeMap<PathBuf, Vec<u8>>) -> Vec<u8> {
+    files
+        .keys()
+        .map(|path| format!("{}\n", path.display()))
+        .collect::<String>()
+        .into_bytes()
+}
+
+#[test]
+fn pages_match_the_golden_files() {
+    let ctx = context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    check("multi-page", &files);
+}
+
+#[test]
+fn one_page_matches_the_golden_files() {
+    let ctx = context();
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    check("one-page", &files);
+}
@@ -0,0 +1,397 @@
+mod common;
+
+use common::{page, TempBook};
+use mdbook_api::sink::MemorySink;
+use mdbook_api::{ApiOnePageRenderer, ApiRenderer};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[test]
+fn toc_separators_and_unnumbered_chapters() {
+    let book = TempBook::new("toc-classes");
+    book.write(
+        "src/SUMMARY.md",
+        "# Summary\n\n[Introduction](intro.md)\n\n- [Users](users.md)\n    - [List users](users/list.md)\n\n---\n\n[Orders](orders.md)\n",
+    )
+    .configure("section_numbers = true");
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let users = page(&files, "users.html");
+    assert!(users
+        .contains(r#"<a href="index.html#introduction" class="toc-h1 toc-link toc-unnumbered" "#));
+    assert!(users.contains(r##"<a href="#users" class="toc-h1 toc-link" data-section="1." "##));
+    assert!(users.contains(r#"class="toc-h2 toc-link" data-section="1.1." "#));
+    assert!(users.contains(r#"class="toc-h1 toc-link toc-unnumbered" data-title="Orders""#));
+    assert!(users.contains(r#"<li class="toc-separator" role="separator"></li>"#));
+    assert!(users.contains(r#"<h1 data-section="1." id="users">"#));
+
+    let css = page(&files, "app.css");
+    assert!(css.contains(".toc-wrapper .toc-separator{"));
+    assert!(css.contains(".toc-wrapper .toc-unnumbered{"));
+}
+
+#[test]
+fn shift_headings_of_nested_chapters() {
+    let book = TempBook::new("shift-headings");
+    book.configure("shift_headings = true");
+
+    let ctx = book.context();
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let index = page(&files, "index.html");
+    // `users/list.md` is nested at depth 2
+    assert!(index.contains(r#"<h1 id="users">Users</h1>"#));
+    assert!(index.contains(r#"<h2 id="list">List</h2>"#));
+    assert!(index.contains(">Errors</h3>"));
+    // The search indexes the shifted headings
+    assert!(index.contains(r#"data-search-headings="h1, h2, h3, h4, h5, h6""#));
+
+    // Headings keep their level without the option
+    let ctx = TempBook::new("shift-headings-off").context();
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let index = page(&files, "index.html");
+    assert!(index.contains(r#"<h1 id="list">List</h1>"#));
+    assert!(index.contains(r#"data-search-headings="h1, h2""#));
+}
+
+#[test]
+fn self_contained_not_found_page_inlines_its_assets() {
+    let book = TempBook::new("self-contained-404");
+    book.write("src/404.md", "# Not here\n\n![Lost](images/lost.png)\n")
+        .write("src/images/lost.png", "not really a png")
+        .configure("self_contained = true\nsite_url = \"https://example.com/docs/\"");
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let not_found = page(&files, "404.html");
+    assert!(not_found.contains("<style>"));
+    assert!(not_found.contains(r#"<img src="data:image/png;base64,"#));
+    let linked = Regex::new(r#"(?:src|href)="[^"]*\.(?:css|js|png)""#).unwrap();
+    assert!(
+        !linked.is_match(&not_found),
+        "{:?}",
+        linked.find(&not_found)
+    );
+    // Links to the pages still lead to the site
+    assert!(not_found.contains(r#"href="https://example.com/docs/users.html#users""#));
+}
+
+#[test]
+fn redirect_pages_of_moved_chapters() {
+    let book = TempBook::new("redirect-pages");
+    book.configure(
+        "[output.api.redirect]\n\"old/users.html\" = \"users.html\"\n\"codes.html\" = \"users/list.html#errors\"",
+    );
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    // The anchor of the old link is kept unless the new one has its own
+    let old_users = page(&files, "old/users.html");
+    assert!(old_users.contains(r#"<meta http-equiv="refresh" content="0; url=../users.html">"#));
+    assert!(old_users.contains(r#"location.replace("../users.html" + location.hash)"#));
+    let codes = page(&files, "codes.html");
+    assert!(codes.contains(r#"location.replace("users/list.html#errors")"#));
+
+    let invalid = [
+        ("unknown-page", "\"old.html\" = \"missing.html\""),
+        ("unknown-anchor", "\"old.html\" = \"users.html#missing\""),
+        ("replaced-page", "\"users.html\" = \"orders.html\""),
+    ];
+    for (name, redirect) in &invalid {
+        let book = TempBook::new(&format!("redirect-{}", name));
+        book.configure(&format!("[output.api.redirect]\n{}", redirect));
+
+        let ctx = book.context();
+        let renderer = ApiRenderer::new(&ctx).unwrap();
+        assert!(
+            renderer.render_to(&ctx, MemorySink::new()).is_err(),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn sitemap_of_the_pages() {
+    let book = TempBook::new("sitemap");
+    book.configure("site_url = \"https://example.com/docs/\"");
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let sitemap = page(&files, "sitemap.xml");
+    let locs: Vec<_> = Regex::new(r"<loc>([^<]+)</loc>\s*<lastmod>\d{4}-\d{2}-\d{2}</lastmod>")
+        .unwrap()
+        .captures_iter(&sitemap)
+        .map(|caps| caps[1].to_owned())
+        .collect();
+    assert_eq!(
+        locs,
+        [
+            "https://example.com/docs/",
+            "https://example.com/docs/users.html",
+            "https://example.com/docs/users/list.html",
+            "https://example.com/docs/orders.html",
+        ]
+    );
+
+    // Crawlers only read robots.txt at the root of the host
+    assert!(!files.contains_key(Path::new("robots.txt")));
+    let list = page(&files, "users/list.html");
+    assert!(
+        list.contains(r#"<link rel="canonical" href="https://example.com/docs/users/list.html">"#)
+    );
+    assert!(list.contains(r#"<meta name="description" content="Listing may fail.">"#));
+
+    let book = TempBook::new("sitemap-host-root");
+    book.configure("site_url = \"https://example.com\"");
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let robots = page(&files, "robots.txt");
+    assert!(robots.contains("Sitemap: https://example.com/sitemap.xml"));
+    assert!(page(&files, "sitemap.xml").contains("<loc>https://example.com/orders.html</loc>"));
+}
+
+#[test]
+fn social_metadata_of_the_pages() {
+    let book = TempBook::new("social-metadata");
+    book.write(
+        "src/users/list.md",
+        "---\ndescription: List the users </script>\n---\n\n# List\n\nListing may fail.\n",
+    )
+    .configure(
+        "site_url = \"https://example.com/docs/\"\nsocial_image = \"img/social.png\"\ntwitter_site = \"@example\"\njson_ld = true",
+    );
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let list = page(&files, "users/list.html");
+    for meta in &[
+        r#"<meta property="og:title" content="List users - Fixture API">"#,
+        r#"<meta property="og:description" content="List the users &lt;/script&gt;">"#,
+        r#"<meta property="og:url" content="https://example.com/docs/users/list.html">"#,
+        r#"<meta property="og:image" content="https://example.com/docs/img/social.png">"#,
+        r#"<meta name="twitter:card" content="summary_large_image">"#,
+        r#"<meta name="twitter:site" content="@example">"#,
+    ] {
+        assert!(list.contains(meta), "{}", meta);
+    }
+
+    let json_ld = Regex::new(r#"<script type="application/ld\+json">(.*)</script>"#)
+        .unwrap()
+        .captures(&list)
+        .map(|caps| caps[1].to_owned())
+        .unwrap();
+    let json_ld: serde_json::Value = serde_json::from_str(&json_ld).unwrap();
+    assert_eq!(json_ld["@type"], "TechArticle");
+    assert_eq!(json_ld["headline"], "List users - Fixture API");
+    assert_eq!(json_ld["description"], "List the users </script>");
+    assert_eq!(json_ld["url"], "https://example.com/docs/users/list.html");
+
+    // The first paragraph describes the pages by default
+    let orders = page(&files, "orders.html");
+    assert!(orders.contains(r#"<meta property="og:description" content="Orders of the users.">"#));
+}
+
+/// Commit every file of the book at `date` by `author`.
+fn commit_all(book: &TempBook, date: &str, author: &str) {
+    for args in &[&["add", "-A"][..], &["commit", "-q", "-m", "Update"][..]] {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(&book.root)
+            .args([
+                "-c",
+                "user.email=author@example.com",
+                "-c",
+                "commit.gpgsign=false",
+            ])
+            .args(*args)
+            .env("GIT_AUTHOR_NAME", author)
+            .env("GIT_COMMITTER_NAME", author)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
+
+#[test]
+fn last_commit_of_the_chapters() {
+    let book = TempBook::new("git-metadata");
+    book.configure("git_metadata = true\nsite_url = \"https://example.com/\"");
+    let init = Command::new("git")
+        .args(["init", "-q"])
+        .arg(&book.root)
+        .status()
+        .unwrap();
+    assert!(init.success());
+    commit_all(&book, "2020-05-17T12:00:00Z", "Ada");
+    book.write(
+        "src/orders.md",
+        "# Orders & Things\n\nOrders of the customers.\n",
+    );
+    commit_all(&book, "2021-01-02T12:00:00Z", "Grace");
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let users = page(&files, "users.html");
+    assert!(users.contains("Last updated 2020-05-17 by Ada (<code>"));
+    let orders = page(&files, "orders.html");
+    assert!(orders.contains("Last updated 2021-01-02 by Grace (<code>"));
+    let sitemap = page(&files, "sitemap.xml");
+    assert!(sitemap
+        .contains("<loc>https://example.com/orders.html</loc>\n    <lastmod>2021-01-02</lastmod>"));
+
+    // Each chapter of the one page output shows its own
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let index = page(&files, "index.html");
+    assert_eq!(
+        index
+            .matches(r#"<time datetime="2020-05-17">2020-05-17</time> by Ada"#)
+            .count(),
+        3
+    );
+    assert_eq!(
+        index
+            .matches(r#"<time datetime="2021-01-02">2021-01-02</time> by Grace"#)
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn edit_links_of_the_chapters() {
+    let book = TempBook::new("edit-url");
+    book.configure(
+        "edit_url_template = \"https://git.example/edit/main/src/{path}?plain=1&ref=docs\"",
+    );
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let list = page(&files, "users/list.html");
+    assert!(list.contains(
+        r#"<a href="https://git.example/edit/main/src/users/list.md?plain=1&amp;ref=docs">Edit this chapter</a>"#
+    ));
+    // The page served for missing paths has no source to edit
+    assert!(!page(&files, "404.html").contains("edit-link"));
+
+    // Each chapter of the one page output links to its own source
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let index = page(&files, "index.html");
+    assert_eq!(index.matches(r#"<p class="edit-link""#).count(), 4);
+    let orders = &index[index.find(r#"id="orders-amp-things""#).unwrap()..];
+    assert!(orders.contains("src/orders.md?plain=1"));
+}
+
+#[test]
+fn navigation_between_the_chapters() {
+    let ctx = TempBook::new("navigation").context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let list = page(&files, "users/list.html");
+    assert!(
+        list.contains(r#"<a href="../users.html" class="previous" rel="prev">&larr; Users</a>"#)
+    );
+    assert!(list.contains(r#"<a href="../orders.html" class="next" rel="next">Orders &rarr;</a>"#));
+    let breadcrumbs = &list[list.find(r#"<nav class="breadcrumbs""#).unwrap()..];
+    let breadcrumbs = &breadcrumbs[..breadcrumbs.find("</nav>").unwrap()];
+    assert!(breadcrumbs.contains(r#"<a href="../users.html">Users</a> &rsaquo;"#));
+    assert!(breadcrumbs.contains("<span>List users</span>"));
+
+    // The first chapter is the index of the book
+    let index = page(&files, "index.html");
+    assert!(!index.contains(r#"rel="prev""#));
+    assert!(!index.contains(r#"<nav class="breadcrumbs""#));
+    assert!(index.contains(r#"<a href="users.html" class="next" rel="next">Users &rarr;</a>"#));
+    let users = page(&files, "users.html");
+    assert!(users.contains(r#"<a href="index.html" class="previous" rel="prev">"#));
+    let orders = page(&files, "orders.html");
+    assert!(orders.contains(r#"<a href="users/list.html" class="previous" rel="prev">"#));
+    assert!(!orders.contains(r#"rel="next""#));
+
+    // The chapters of the one page output follow each other
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+    assert!(!page(&files, "index.html").contains(r#"<nav class="chapter-nav""#));
+}
+
+/// Render the fixture book in `language` with extra `output.api` lines.
+fn render_in(name: &str, language: &str, config: &str) -> BTreeMap<PathBuf, Vec<u8>> {
+    let book = TempBook::new(name);
+    book.write(
+        "book.toml",
+        &format!(
+            "[book]\ntitle = \"Fixture API\"\nsrc = \"src\"\nlanguage = \"{}\"\n\n[output.api]\n{}\n",
+            language, config
+        ),
+    );
+
+    let ctx = book.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    renderer.render_to(&ctx, MemorySink::new()).unwrap()
+}
+
+#[test]
+fn strings_in_the_language_of_the_book() {
+    let files = render_in(
+        "i18n-fr",
+        "fr",
+        "[output.api.i18n]\nsign_up = \"Demander une clé\"",
+    );
+
+    let users = page(&files, "users.html");
+    assert!(users.contains(r#"<html lang="fr" dir="ltr">"#));
+    assert!(users.contains(r#"placeholder="Rechercher""#));
+    assert!(users.contains("<li><a href='#'>Demander une clé</a></li>"));
+    assert!(users.contains(r#""no_results":"Aucun résultat pour « {query} »""#));
+    assert!(page(&files, "404.html").contains("Page introuvable"));
+
+    // The theme folder overlays the strings of a regional language
+    let regional = TempBook::new("i18n-fr-ca");
+    regional
+        .write("theme/i18n/fr-CA.toml", "nav = \"SOMMAIRE\"\n")
+        .write(
+            "book.toml",
+            "[book]\ntitle = \"Fixture API\"\nsrc = \"src\"\nlanguage = \"fr-CA\"\n\n[output.api]\n",
+        );
+    let ctx = regional.context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+    let users = page(&files, "users.html");
+    assert!(users.contains("SOMMAIRE"));
+    assert!(users.contains(r#"placeholder="Rechercher""#));
+
+    // Languages without built-in strings fall back to English
+    let files = render_in("i18n-ar", "ar", "");
+    let users = page(&files, "users.html");
+    assert!(users.contains(r#"<html lang="ar" dir="rtl">"#));
+    assert!(users.contains(r#"placeholder="Search""#));
+}
@@ -0,0 +1,92 @@
+use mdbook_api::sink::MemorySink;
+use mdbook_api::{ApiOnePageRenderer, ApiRenderer};
+
+use mdbook::renderer::RenderContext;
+use mdbook::MDBook;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/book");
+
+fn context() -> RenderContext {
+    let md = MDBook::load(FIXTURE).unwrap();
+    let destination = md.build_dir_for("api");
+    RenderContext::new(md.root, md.book, md.config, destination)
+}
+
+fn page(files: &BTreeMap<PathBuf, Vec<u8>>, path: &str) -> String {
+    let content = files
+        .get(Path::new(path))
+        .unwrap_or_else(|| panic!("{} is not rendered", path));
+    String::from_utf8(content.clone()).unwrap()
+}
+
+/// Links of the table of contents of a page.
+fn toc_links(html: &str) -> Vec<String> {
+    let toc = &html[html.find("id=\"toc\"").unwrap()..];
+    let toc = &toc[..toc.find("</ul>\n").unwrap_or(toc.len())];
+
+    Regex::new(r#"<a href="([^"]*)" class="toc-h\d toc-link""#)
+        .unwrap()
+        .captures_iter(toc)
+        .map(|caps| caps[1].to_owned())
+        .collect()
+}
+
+#[test]
+fn one_page_in_memory() {
+    let ctx = context();
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    assert!(!files.contains_key(Path::new("users.html")));
+
+    let index = page(&files, "index.html");
+    assert!(index.contains("<h1 id=\"users\">Users</h1>"));
+    assert!(index.contains("<h1 id=\"orders-amp-things\">Orders &amp; Things</h1>"));
+    // Links to the other chapters target their heading in the page, the ids
+    // shared by several chapters are made unique
+    assert!(index.contains("<h2 id=\"errors\">Errors</h2>"));
+    assert!(index.contains("<h2 id=\"errors-1\">Errors</h2>"));
+    assert!(index.contains("href=\"#errors-1\""));
+    assert!(!index.contains("users/list.html"));
+
+    let links = toc_links(&index);
+    assert!(links.contains(&"#users".to_owned()));
+    assert!(links.contains(&"#list".to_owned()));
+}
+
+#[test]
+fn one_page_not_found_links_to_the_page() {
+    let ctx = context();
+    let renderer = ApiOnePageRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    let links = toc_links(&page(&files, "404.html"));
+    assert!(!links.is_empty());
+    for link in &links {
+        assert!(link.starts_with("/index.html#"), "{}", link);
+    }
+    assert!(links.contains(&"/index.html#list".to_owned()));
+}
+
+#[test]
+fn pages_in_memory() {
+    let ctx = context();
+    let renderer = ApiRenderer::new(&ctx).unwrap();
+    let files = renderer.render_to(&ctx, MemorySink::new()).unwrap();
+
+    for path in &["index.html", "users.html", "users/list.html", "orders.html"] {
+        page(&files, path);
+    }
+    assert!(files.contains_key(Path::new("anchors.json")));
+
+    let list = page(&files, "users/list.html");
+    assert!(list.contains("<title>List users - Fixture API</title>"));
+    assert!(list.contains("<h2 id=\"errors\">Errors</h2>"));
+    assert!(toc_links(&list).contains(&"../users.html#users".to_owned()));
+
+    let links = toc_links(&page(&files, "404.html"));
+    assert!(links.contains(&"/users/list.html#list".to_owned()));
+}
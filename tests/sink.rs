@@ -0,0 +1,73 @@
+use mdbook_api::sink::{Sink, TarSink, ZipSink};
+
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// Write two files, appending to each while it is the last one written.
+fn write_files<S: Sink>(sink: &mut S) {
+    sink.write(Path::new("index.html"), b"<p>one</p>").unwrap();
+    sink.append(Path::new("index.html"), b"<p>two</p>").unwrap();
+    sink.write(Path::new("users/list.html"), b"list").unwrap();
+    sink.append(Path::new("users/list.html"), b" of users")
+        .unwrap();
+}
+
+#[test]
+fn zip_round_trip() {
+    let mut sink = ZipSink::new(Cursor::new(Vec::new()));
+    write_files(&mut sink);
+    let mut archive = zip::ZipArchive::new(sink.finish().unwrap()).unwrap();
+
+    let mut read = |name: &str| {
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    };
+    assert_eq!(read("index.html"), "<p>one</p><p>two</p>");
+    assert_eq!(read("users/list.html"), "list of users");
+}
+
+#[test]
+fn tar_round_trip() {
+    let mut sink = TarSink::new(Vec::new());
+    write_files(&mut sink);
+    let bytes = sink.finish().unwrap();
+
+    let mut archive = tar::Archive::new(bytes.as_slice());
+    let files = archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().into_owned();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            (path, content)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        files,
+        vec![
+            ("index.html".to_owned(), "<p>one</p><p>two</p>".to_owned()),
+            ("users/list.html".to_owned(), "list of users".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn append_only_to_the_last_file() {
+    let mut zip = ZipSink::new(Cursor::new(Vec::new()));
+    write_files(&mut zip);
+    assert!(zip.append(Path::new("index.html"), b"three").is_err());
+    assert!(zip.append(Path::new("orders.html"), b"three").is_err());
+
+    let mut tar = TarSink::new(Vec::new());
+    write_files(&mut tar);
+    assert!(tar.append(Path::new("index.html"), b"three").is_err());
+    assert!(tar.append(Path::new("orders.html"), b"three").is_err());
+}
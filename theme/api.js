@@ -0,0 +1,218 @@
+// Scripts of mdbook-api, loaded after the Slate bundle of `app.js`.
+
+// Follow the redirects of the removed anchors, given by the page in its
+// `data-anchor-redirects` attribute
+$(function () {
+  "use strict";
+
+  var redirects = {};
+  try {
+    redirects = JSON.parse(document.body.getAttribute("data-anchor-redirects") || "{}");
+  } catch (e) {}
+
+  function followRedirect() {
+    var id = decodeURIComponent(location.hash.slice(1));
+    var target = redirects[id];
+
+    // An anchor which is back in the page wins over its redirect
+    if (id && target && !document.getElementById(id)) {
+      location.replace(target);
+    }
+  }
+
+  followRedirect();
+  $(window).on("hashchange", followRedirect);
+});
+
+// Colour scheme chosen with the toggle of the sidebar, the system one until
+// then. It is applied before the page is displayed to avoid a flash.
+(function () {
+  "use strict";
+
+  var key = "mdbook-api-color-scheme";
+  var root = document.documentElement;
+  var stored = null;
+  try {
+    stored = localStorage.getItem(key);
+  } catch (e) {}
+
+  function apply(scheme) {
+    root.setAttribute("data-color-scheme", scheme);
+    // Enable the code palette of the scheme only
+    $("[data-code-scheme]").each(function () {
+      this.media = this.getAttribute("data-code-scheme") === scheme ? "all" : "not all";
+    });
+  }
+
+  function current() {
+    var scheme = root.getAttribute("data-color-scheme");
+    if (scheme) {
+      return scheme;
+    }
+    var dark = window.matchMedia && window.matchMedia("(prefers-color-scheme: dark)").matches;
+    return dark ? "dark" : "light";
+  }
+
+  if (stored) {
+    root.setAttribute("data-color-scheme", stored);
+  }
+
+  $(function () {
+    if (stored) {
+      apply(stored);
+    }
+
+    $(".color-scheme-toggle").on("click", function () {
+      var scheme = current() === "dark" ? "light" : "dark";
+      apply(scheme);
+      try {
+        localStorage.setItem(key, scheme);
+      } catch (e) {}
+    });
+  });
+})();
+
+// Search the sections of the page, a heading and the content up to the next
+// heading, with lunr. It replaces the search of the Slate bundle to leave out
+// the excluded sections and to show its message in the language of the book.
+(function () {
+  "use strict";
+
+  var headings = "h1, h2";
+  var highlightOptions = { element: "span", className: "search-highlight" };
+  var searchDelay = 0;
+  var timeout = 0;
+  var content, searchResults;
+
+  var index = new lunr.Index();
+  index.ref("id");
+  index.field("title", { boost: 10 });
+  index.field("body");
+  index.pipeline.add(lunr.trimmer, lunr.stopWordFilter);
+
+  $(populate);
+  $(bind);
+
+  function populate() {
+    headings = document.body.getAttribute("data-search-headings") || headings;
+
+    $(headings).not("[data-search-exclude]").each(function () {
+      var title = $(this);
+      var body = title.nextUntil(headings).not("[data-search-exclude]");
+      index.add({
+        id: title.prop("id"),
+        title: title.text(),
+        body: body.text()
+      });
+    });
+
+    // Wait for the user to stop typing in big books
+    if (index.tokenStore.length > 5000) {
+      searchDelay = 300;
+    }
+  }
+
+  function bind() {
+    content = $(".content");
+    searchResults = $(".search-results");
+
+    $("#input-search").on("keyup", function (event) {
+      clearTimeout(timeout);
+      timeout = setTimeout(function () {
+        search(event);
+      }, searchDelay);
+    });
+  }
+
+  function search(event) {
+    var input = $("#input-search")[0];
+
+    unhighlight();
+    searchResults.addClass("visible");
+
+    // Escape clears the search
+    if (event.keyCode === 27) {
+      input.value = "";
+    }
+
+    if (!input.value) {
+      searchResults.removeClass("visible");
+      return;
+    }
+
+    var results = index.search(input.value).filter(function (result) {
+      return result.score > 0.0001;
+    });
+
+    if (results.length) {
+      searchResults.empty();
+      $.each(results, function (i, result) {
+        var heading = document.getElementById(result.ref);
+        searchResults.append("<li><a href='#" + result.ref + "'>" + $(heading).text() + "</a></li>");
+      });
+      highlight(input.value);
+    } else {
+      searchResults.html("<li></li>");
+      $(".search-results li").text(noResults(input.value));
+    }
+  }
+
+  // Message of an empty search, from the `data-strings` of the page
+  function noResults(query) {
+    var strings = {};
+    try {
+      strings = JSON.parse(document.body.getAttribute("data-strings") || "{}");
+    } catch (e) {}
+
+    var message = strings.no_results || 'No Results Found for "{query}"';
+    return message.replace("{query}", function () {
+      return query;
+    });
+  }
+
+  function highlight(query) {
+    content.highlight(query, highlightOptions);
+  }
+
+  function unhighlight() {
+    content.unhighlight(highlightOptions);
+  }
+})();
+
+// Keep the section being read when switching to another edition of the book.
+// `data-locale-anchors` gives the heading ids of the chapters of the page in
+// every edition, the heading at the same place is targeted.
+$(function () {
+  "use strict";
+
+  var selector = $(".locale-selector");
+  var anchors = {};
+  try {
+    anchors = JSON.parse(selector.attr("data-locale-anchors") || "{}");
+  } catch (e) {}
+
+  var current = anchors[document.documentElement.lang] || [];
+
+  selector.on("click", "a[data-locale]", function () {
+    var id = decodeURIComponent(location.hash.slice(1));
+    var target = anchors[this.getAttribute("data-locale")] || [];
+    if (!id) {
+      return;
+    }
+
+    for (var chapter = 0; chapter < current.length; chapter++) {
+      var position = current[chapter].indexOf(id);
+      if (position === -1) {
+        continue;
+      }
+
+      // Fall back to the first heading of the chapter
+      var ids = target[chapter] || [];
+      var hash = ids[position] || ids[0];
+      if (hash) {
+        this.hash = hash;
+      }
+      break;
+    }
+  });
+});